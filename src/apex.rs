@@ -0,0 +1,856 @@
+use core::fmt::Debug;
+
+use embedded_hal::{delay::DelayNs, i2c::I2c};
+
+use crate::{
+    config::{DmpInit, DmpMemReset, FreefallEnable, PedEnable, SmdEnable, TiltEnable},
+    error::SensorError,
+    register::{Bank0, Mreg1, RegisterBank},
+    Activity,
+    DmpOdr,
+    DmpPowerSave,
+    Error,
+    FreefallMaxDistance,
+    FreefallMinDistance,
+    Icm42670,
+    InterruptPin,
+    PedHighEnergyTh,
+    PedSensitivity,
+    SmdSensitivity,
+    TiltWaitTime,
+};
+
+/// Pedometer tuning parameters
+///
+/// The defaults match the recommended values given in the datasheet. These
+/// parameters are loaded by the DMP when it is initialized, so changes only
+/// take effect after the next call to [Icm42670::init_apex].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PedometerConfig {
+    /// Minimum peak amplitude for a step to be detected, in mg (30 to 90, in
+    /// steps of 4)
+    pub amp_threshold_mg: u8,
+    /// Number of consecutive steps required before the step count starts to
+    /// increase (0 to 15)
+    pub step_count_threshold: u8,
+    /// Number of steps required before steps are reported while the activity
+    /// is unknown (0 to 7)
+    pub step_detect_threshold: u8,
+    /// Number of samples without a step after which the step buffer is
+    /// cleared (50 to 225, in steps of 25)
+    pub step_buffer_timeout: u8,
+    /// Threshold used to reject high-energy, non-walking motion
+    pub high_energy_threshold: PedHighEnergyTh,
+    /// Minimum amplitude for motion to be considered for step detection, in mg
+    /// (30 to 105, in steps of 5)
+    pub low_energy_amp_threshold_mg: u8,
+    /// Sensitivity mode
+    pub sensitivity: PedSensitivity,
+}
+
+impl Default for PedometerConfig {
+    fn default() -> Self {
+        Self {
+            amp_threshold_mg: 62,
+            step_count_threshold: 5,
+            step_detect_threshold: 2,
+            step_buffer_timeout: 150,
+            high_energy_threshold: PedHighEnergyTh::default(),
+            low_energy_amp_threshold_mg: 80,
+            sensitivity: PedSensitivity::default(),
+        }
+    }
+}
+
+/// Low-g detection parameters
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LowGConfig {
+    /// Acceleration below which a low-g condition is detected, in mg (31.25 to
+    /// 1000, in steps of 31.25)
+    pub peak_threshold_mg: u16,
+    /// Hysteresis applied to the peak threshold, in mg (31.25 to 250, in steps
+    /// of 31.25)
+    pub hysteresis_mg: u16,
+    /// Number of samples the acceleration must remain below the threshold for
+    /// (1 to 8)
+    pub time_threshold: u8,
+}
+
+impl Default for LowGConfig {
+    fn default() -> Self {
+        Self {
+            peak_threshold_mg: 563,
+            hysteresis_mg: 156,
+            time_threshold: 1,
+        }
+    }
+}
+
+/// High-g detection parameters
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HighGConfig {
+    /// Acceleration above which a high-g condition is detected, in mg (250 to
+    /// 8000, in steps of 250)
+    pub peak_threshold_mg: u16,
+    /// Hysteresis applied to the peak threshold, in mg (31.25 to 250, in steps
+    /// of 31.25)
+    pub hysteresis_mg: u16,
+    /// Number of samples the acceleration must remain above the threshold for
+    /// (1 to 8)
+    pub time_threshold: u8,
+}
+
+impl Default for HighGConfig {
+    fn default() -> Self {
+        Self {
+            peak_threshold_mg: 2500,
+            hysteresis_mg: 156,
+            time_threshold: 1,
+        }
+    }
+}
+
+/// Freefall detection parameters
+///
+/// A freefall starts with a low-g condition and ends with the high-g
+/// condition caused by the impact. As with the other APEX parameters these are
+/// loaded when the DMP is initialized, so changes only take effect after the
+/// next call to [Icm42670::init_apex].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FreefallConfig {
+    /// Low-g detection parameters
+    pub low_g: LowGConfig,
+    /// High-g detection parameters
+    pub high_g: HighGConfig,
+    /// Time after a freefall during which further freefalls are ignored
+    pub debounce_duration: FreefallDebounce,
+    /// Minimum distance which must be fallen, 12 cm by default as on reset
+    pub min_distance: FreefallMinDistance,
+    /// Maximum distance which may be fallen, 204 cm by default as on reset
+    pub max_distance: FreefallMaxDistance,
+}
+
+/// Freefall debounce duration, in milliseconds
+///
+/// Must be either 0, or between 1250 and 3000 in steps of 125.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FreefallDebounce(pub u16);
+
+impl Default for FreefallDebounce {
+    fn default() -> Self {
+        Self(2000)
+    }
+}
+
+/// APEX motion features which are enabled in `APEX_CONFIG1`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ApexFeatures {
+    /// The pedometer is enabled
+    pub pedometer: bool,
+    /// Tilt detection is enabled
+    pub tilt: bool,
+    /// Freefall, low-g and high-g detection are enabled
+    pub freefall: bool,
+    /// Significant motion detection is enabled
+    pub smd: bool,
+}
+
+impl ApexFeatures {
+    /// Is any feature enabled?
+    pub fn any(&self) -> bool {
+        self.pedometer || self.tilt || self.freefall || self.smd
+    }
+
+    fn from_bits(bits: u8) -> Self {
+        // `SMD_ENABLE`, `FF_ENABLE`, `TILT_ENABLE` and `PED_ENABLE` occupy bits 6:3
+        Self {
+            pedometer: bits & 0b0000_1000 != 0,
+            tilt: bits & 0b0001_0000 != 0,
+            freefall: bits & 0b0010_0000 != 0,
+            smd: bits & 0b0100_0000 != 0,
+        }
+    }
+
+    fn bits(&self) -> u8 {
+        (self.pedometer as u8) << 3
+            | (self.tilt as u8) << 4
+            | (self.freefall as u8) << 5
+            | (self.smd as u8) << 6
+    }
+}
+
+impl<I2C, E> Icm42670<I2C>
+where
+    I2C: I2c<Error = E>,
+    E: Debug,
+{
+    /// Initialize the DMP so that the APEX motion features can be used
+    ///
+    /// The accelerometer must already be running (in either low-power or
+    /// low-noise mode) at the output data rate given by [DmpOdr::accel_odr],
+    /// otherwise an error is returned. Any APEX features which were previously
+    /// enabled are disabled, and must be re-enabled after initialization.
+    pub fn init_apex(&mut self, delay: &mut impl DelayNs, odr: DmpOdr) -> Result<(), Error<E>> {
+        let mode = self.power_mode()?;
+        let accel_running = mode.accel_low_power() || mode.accel_low_noise();
+        if !accel_running || self.accel_odr()? != odr.accel_odr() {
+            return Err(Error::SensorError(SensorError::UnsupportedApexOdr));
+        }
+
+        // Disable all features while the DMP is being (re)initialized.
+        // `SMD_ENABLE`, `FF_ENABLE`, `TILT_ENABLE` and `PED_ENABLE` occupy bits 6:3
+        self.modify_reg(&Bank0::APEX_CONFIG1, 0b0111_1000, 0)?;
+        self.update_reg(odr)?;
+
+        // See "APEX ODR SUPPORT" and "DMP INITIALIZATION" in the datasheet.
+        self.update_reg(DmpMemReset::Enabled)?;
+        delay.delay_ms(1);
+
+        self.update_reg(DmpInit::Enabled)?;
+        delay.delay_ms(50);
+
+        Ok(())
+    }
+
+    /// Return the output data rate at which the DMP is configured to run
+    pub fn apex_odr(&mut self) -> Result<DmpOdr, Error<E>> {
+        // `DMP_ODR` occupies bits 1:0 in the register
+        let odr = self.read_reg(&Bank0::APEX_CONFIG1)? & 0b11;
+        let odr = DmpOdr::try_from(odr)?;
+
+        Ok(odr)
+    }
+
+    /// Return the APEX motion features which are currently enabled
+    pub fn apex_features(&mut self) -> Result<ApexFeatures, Error<E>> {
+        let bits = self.read_reg(&Bank0::APEX_CONFIG1)?;
+
+        Ok(ApexFeatures::from_bits(bits))
+    }
+
+    /// Enable exactly the given APEX motion features, disabling all others
+    ///
+    /// The DMP must first have been initialized with [Self::init_apex].
+    pub fn set_apex_features(&mut self, features: ApexFeatures) -> Result<(), Error<E>> {
+        self.modify_reg(&Bank0::APEX_CONFIG1, 0b0111_1000, features.bits())
+    }
+
+    /// Enable or disable the DMP power save mode
+    ///
+    /// While enabled the DMP is idle until woken by Wake-on-Motion, which
+    /// must therefore be configured for the APEX features to keep running.
+    pub fn set_dmp_power_save(&mut self, mode: DmpPowerSave) -> Result<(), Error<E>> {
+        self.update_reg(mode)
+    }
+
+    /// Enable the pedometer
+    ///
+    /// The DMP must first have been initialized with [Self::init_apex].
+    pub fn enable_pedometer(&mut self) -> Result<(), Error<E>> {
+        self.update_reg(PedEnable::Enabled)
+    }
+
+    /// Disable the pedometer
+    pub fn disable_pedometer(&mut self) -> Result<(), Error<E>> {
+        self.update_reg(PedEnable::Disabled)
+    }
+
+    /// Return the number of steps counted by the pedometer
+    pub fn step_count(&mut self) -> Result<u16, Error<E>> {
+        // `STEP_CNT` is split across `APEX_DATA1` (high byte) and `APEX_DATA0`
+        let hi = self.read_reg(&Bank0::APEX_DATA1)?;
+        let lo = self.read_reg(&Bank0::APEX_DATA0)?;
+
+        Ok(u16::from_be_bytes([hi, lo]))
+    }
+
+    /// Return the current step cadence, in steps per second
+    ///
+    /// Returns zero if no steps have been detected yet.
+    pub fn step_cadence(&mut self) -> Result<f32, Error<E>> {
+        // `STEP_CADENCE` is the number of DMP samples between two steps, in u6.2
+        // fixed-point format.
+        let cadence = self.read_reg(&Bank0::APEX_DATA2)?;
+        if cadence == 0 {
+            return Ok(0.0);
+        }
+
+        let odr = self.apex_odr()?.as_f32();
+        let samples = cadence as f32 / 4.0;
+
+        Ok(odr / samples)
+    }
+
+    /// Return the activity currently detected by the pedometer
+    pub fn activity(&mut self) -> Result<Activity, Error<E>> {
+        // `ACTIVITY_CLASS` occupies bits 1:0 in the register
+        let class = self.read_reg(&Bank0::APEX_DATA3)? & 0b11;
+        let activity = Activity::try_from(class)?;
+
+        Ok(activity)
+    }
+
+    /// Reset the pedometer step count to zero
+    ///
+    /// The step count is held in DMP memory, so this re-initializes the DMP at
+    /// its current output data rate and then re-enables whichever APEX
+    /// features were previously enabled.
+    pub fn reset_step_count(&mut self, delay: &mut impl DelayNs) -> Result<(), Error<E>> {
+        let features = self.apex_features()?;
+        let odr = self.apex_odr()?;
+
+        self.init_apex(delay, odr)?;
+        self.set_apex_features(features)
+    }
+
+    /// Configure the pedometer tuning parameters
+    ///
+    /// Returns an error without writing anything if any parameter is out of
+    /// range.
+    pub fn set_pedometer_config(
+        &mut self,
+        delay: &mut impl DelayNs,
+        config: &PedometerConfig,
+    ) -> Result<(), Error<E>> {
+        let pedometer = PedometerSelectors::new(config)?;
+
+        // `LOW_ENERGY_AMP_TH_SEL` occupies bits 3:0, sharing the register with
+        // `DMP_POWER_SAVE_TIME_SEL`.
+        self.modify_mreg(
+            delay,
+            RegisterBank::MReg1,
+            &Mreg1::APEX_CONFIG2,
+            0b0000_1111,
+            pedometer.low_energy_amp_threshold,
+        )?;
+        // `PED_AMP_TH_SEL` occupies bits 7:4 and `PED_STEP_CNT_TH_SEL` bits 3:0
+        self.write_mreg(
+            delay,
+            RegisterBank::MReg1,
+            &Mreg1::APEX_CONFIG3,
+            pedometer.amp_threshold << 4 | pedometer.step_count_threshold,
+        )?;
+        // `PED_STEP_DET_TH_SEL` occupies bits 7:5, `PED_SB_TIMER_TH_SEL` bits 4:2
+        // and `PED_HI_EN_TH_SEL` bits 1:0
+        self.write_mreg(
+            delay,
+            RegisterBank::MReg1,
+            &Mreg1::APEX_CONFIG4,
+            pedometer.step_detect_threshold << 5
+                | pedometer.step_buffer_timeout << 2
+                | config.high_energy_threshold as u8,
+        )?;
+        // `SENSITIVITY_MODE` occupies bit 0
+        self.modify_mreg(
+            delay,
+            RegisterBank::MReg1,
+            &Mreg1::APEX_CONFIG9,
+            0b0000_0001,
+            config.sensitivity as u8,
+        )?;
+
+        Ok(())
+    }
+
+    /// Return the configured pedometer tuning parameters
+    pub fn pedometer_config(
+        &mut self,
+        delay: &mut impl DelayNs,
+    ) -> Result<PedometerConfig, Error<E>> {
+        let config2 = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::APEX_CONFIG2)?;
+        let config3 = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::APEX_CONFIG3)?;
+        let config4 = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::APEX_CONFIG4)?;
+        let config9 = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::APEX_CONFIG9)?;
+
+        Ok(PedometerConfig {
+            amp_threshold_mg: 30 + 4 * (config3 >> 4),
+            step_count_threshold: config3 & 0xF,
+            step_detect_threshold: config4 >> 5,
+            step_buffer_timeout: 50 + 25 * ((config4 >> 2) & 0x7),
+            high_energy_threshold: PedHighEnergyTh::try_from(config4 & 0b11)?,
+            low_energy_amp_threshold_mg: 30 + 5 * (config2 & 0xF),
+            sensitivity: PedSensitivity::try_from(config9 & 0b1)?,
+        })
+    }
+
+    /// Enable tilt detection, reporting a tilt once the device has remained
+    /// tilted for the given time
+    ///
+    /// The DMP must first have been initialized with [Self::init_apex], and the
+    /// event can be routed to an interrupt pin using
+    /// [Self::set_apex_interrupt].
+    pub fn enable_tilt(
+        &mut self,
+        delay: &mut impl DelayNs,
+        wait: TiltWaitTime,
+    ) -> Result<(), Error<E>> {
+        self.set_tilt_wait_time(delay, wait)?;
+
+        self.update_reg(TiltEnable::Enabled)
+    }
+
+    /// Return the time for which the device must remain tilted before a tilt
+    /// is reported
+    pub fn tilt_wait_time(&mut self, delay: &mut impl DelayNs) -> Result<TiltWaitTime, Error<E>> {
+        // `TILT_WAIT_TIME_SEL` occupies bits 7:6 in the register
+        let config5 = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::APEX_CONFIG5)?;
+        let wait = TiltWaitTime::try_from(config5 >> 6)?;
+
+        Ok(wait)
+    }
+
+    /// Set the time for which the device must remain tilted before a tilt is
+    /// reported, without enabling tilt detection
+    pub fn set_tilt_wait_time(
+        &mut self,
+        delay: &mut impl DelayNs,
+        wait: TiltWaitTime,
+    ) -> Result<(), Error<E>> {
+        // `TILT_WAIT_TIME_SEL` occupies bits 7:6 in the register
+        self.modify_mreg(
+            delay,
+            RegisterBank::MReg1,
+            &Mreg1::APEX_CONFIG5,
+            0b1100_0000,
+            (wait as u8) << 6,
+        )
+    }
+
+    /// Disable tilt detection
+    pub fn disable_tilt(&mut self) -> Result<(), Error<E>> {
+        self.update_reg(TiltEnable::Disabled)
+    }
+
+    /// Configure the freefall detection parameters
+    ///
+    /// Returns an error without writing anything if any parameter is out of
+    /// range.
+    pub fn set_freefall_config(
+        &mut self,
+        delay: &mut impl DelayNs,
+        config: &FreefallConfig,
+    ) -> Result<(), Error<E>> {
+        let freefall = FreefallSelectors::new(config)?;
+
+        self.write_low_g(delay, freefall.low_g)?;
+        self.write_high_g(delay, freefall.high_g)?;
+
+        // `FF_DEBOUNCE_DURATION_SEL` occupies bits 7:4 in the register
+        self.modify_mreg(
+            delay,
+            RegisterBank::MReg1,
+            &Mreg1::APEX_CONFIG9,
+            0b1111_0000,
+            freefall.debounce << 4,
+        )?;
+        // `FF_MAX_DURATION_SEL` occupies bits 7:4 and `FF_MIN_DURATION_SEL` 3:0
+        self.write_mreg(
+            delay,
+            RegisterBank::MReg1,
+            &Mreg1::APEX_CONFIG12,
+            (config.max_distance as u8) << 4 | config.min_distance as u8,
+        )?;
+
+        Ok(())
+    }
+
+    /// Return the configured freefall detection parameters
+    ///
+    /// Thresholds are rounded to the nearest mg.
+    pub fn freefall_config(
+        &mut self,
+        delay: &mut impl DelayNs,
+    ) -> Result<FreefallConfig, Error<E>> {
+        let config5 = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::APEX_CONFIG5)?;
+        let config9 = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::APEX_CONFIG9)?;
+        let config10 = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::APEX_CONFIG10)?;
+        let config11 = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::APEX_CONFIG11)?;
+        let config12 = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::APEX_CONFIG12)?;
+
+        let debounce = match config9 >> 4 {
+            0 => 0,
+            sel => 1250 + 125 * (sel as u16 - 1),
+        };
+
+        Ok(FreefallConfig {
+            low_g: LowGConfig {
+                peak_threshold_mg: thirty_seconds_mg(config10 >> 3),
+                hysteresis_mg: thirty_seconds_mg((config5 >> 3) & 0x7),
+                time_threshold: (config10 & 0x7) + 1,
+            },
+            high_g: HighGConfig {
+                peak_threshold_mg: 250 + 250 * (config11 >> 3) as u16,
+                hysteresis_mg: thirty_seconds_mg(config5 & 0x7),
+                time_threshold: (config11 & 0x7) + 1,
+            },
+            debounce_duration: FreefallDebounce(debounce),
+            min_distance: FreefallMinDistance::try_from(config12 & 0xF)?,
+            max_distance: FreefallMaxDistance::try_from(config12 >> 4)?,
+        })
+    }
+
+    /// Configure the low-g detection parameters
+    ///
+    /// Low-g and high-g detection run as part of the freefall engine, so are
+    /// enabled using [Self::enable_freefall]. Their events are reported in the
+    /// [crate::InterruptStatus] and can be routed to an interrupt pin using
+    /// [Self::set_apex_interrupt].
+    pub fn set_low_g_config(
+        &mut self,
+        delay: &mut impl DelayNs,
+        config: &LowGConfig,
+    ) -> Result<(), Error<E>> {
+        let low_g = LowGSelectors::new(config)?;
+
+        self.write_low_g(delay, low_g)
+    }
+
+    /// Configure the high-g detection parameters
+    ///
+    /// See [Self::set_low_g_config] for how high-g detection is enabled.
+    pub fn set_high_g_config(
+        &mut self,
+        delay: &mut impl DelayNs,
+        config: &HighGConfig,
+    ) -> Result<(), Error<E>> {
+        let high_g = HighGSelectors::new(config)?;
+
+        self.write_high_g(delay, high_g)
+    }
+
+    /// Enable freefall detection
+    ///
+    /// The DMP must first have been initialized with [Self::init_apex].
+    pub fn enable_freefall(&mut self) -> Result<(), Error<E>> {
+        self.update_reg(FreefallEnable::Enabled)
+    }
+
+    /// Disable freefall detection
+    pub fn disable_freefall(&mut self) -> Result<(), Error<E>> {
+        self.update_reg(FreefallEnable::Disabled)
+    }
+
+    /// Return the duration of the most recently detected freefall, in
+    /// milliseconds
+    pub fn freefall_duration_ms(&mut self) -> Result<f32, Error<E>> {
+        // `FF_DUR` is the number of DMP samples for which the freefall lasted,
+        // split across `APEX_DATA4` (high byte) and `APEX_DATA5`.
+        let hi = self.read_reg(&Bank0::APEX_DATA4)?;
+        let lo = self.read_reg(&Bank0::APEX_DATA5)?;
+        let samples = u16::from_be_bytes([hi, lo]);
+
+        let odr = self.apex_odr()?.as_f32();
+
+        Ok(samples as f32 * 1000.0 / odr)
+    }
+
+    /// Enable significant motion detection with the given sensitivity
+    ///
+    /// Significant motion detection is built on top of Wake-on-Motion, which
+    /// must also be configured and enabled. The DMP must first have been
+    /// initialized with [Self::init_apex]; note that the sensitivity is only
+    /// loaded by the DMP during initialization. The observation window is fixed
+    /// by the DMP firmware and is not configurable on this device.
+    pub fn enable_smd(
+        &mut self,
+        delay: &mut impl DelayNs,
+        sensitivity: SmdSensitivity,
+    ) -> Result<(), Error<E>> {
+        self.set_smd_sensitivity(delay, sensitivity)?;
+
+        self.update_reg(SmdEnable::Enabled)
+    }
+
+    /// Return the configured significant motion detection sensitivity
+    pub fn smd_sensitivity(
+        &mut self,
+        delay: &mut impl DelayNs,
+    ) -> Result<SmdSensitivity, Error<E>> {
+        // `SMD_SENSITIVITY_SEL` occupies bits 3:1 in the register
+        let config9 = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::APEX_CONFIG9)?;
+        let sensitivity = SmdSensitivity::try_from((config9 >> 1) & 0x7)?;
+
+        Ok(sensitivity)
+    }
+
+    /// Set the significant motion detection sensitivity, without enabling
+    /// significant motion detection
+    ///
+    /// As with [Self::enable_smd], the sensitivity is only loaded by the DMP
+    /// during initialization.
+    pub fn set_smd_sensitivity(
+        &mut self,
+        delay: &mut impl DelayNs,
+        sensitivity: SmdSensitivity,
+    ) -> Result<(), Error<E>> {
+        // `SMD_SENSITIVITY_SEL` occupies bits 3:1 in the register
+        self.modify_mreg(
+            delay,
+            RegisterBank::MReg1,
+            &Mreg1::APEX_CONFIG9,
+            0b0000_1110,
+            (sensitivity as u8) << 1,
+        )
+    }
+
+    /// Disable significant motion detection
+    pub fn disable_smd(&mut self) -> Result<(), Error<E>> {
+        self.update_reg(SmdEnable::Disabled)
+    }
+
+    /// Enable or disable routing of the significant motion detection interrupt
+    /// to the given pin
+    pub fn set_smd_interrupt(&mut self, pin: InterruptPin, enabled: bool) -> Result<(), Error<E>> {
+        // `SMD_INTn_EN` occupies bit 3 in the register
+        let bits = if enabled { 0b0000_1000 } else { 0 };
+
+        self.modify_reg(&pin.source1(), 0b0000_1000, bits)
+    }
+
+    fn write_low_g(
+        &mut self,
+        delay: &mut impl DelayNs,
+        low_g: LowGSelectors,
+    ) -> Result<(), Error<E>> {
+        // `LOWG_PEAK_TH_HYST_SEL` occupies bits 5:3 in the register
+        self.modify_mreg(
+            delay,
+            RegisterBank::MReg1,
+            &Mreg1::APEX_CONFIG5,
+            0b0011_1000,
+            low_g.hysteresis << 3,
+        )?;
+        // `LOWG_PEAK_TH_SEL` occupies bits 7:3 and `LOWG_TIME_TH_SEL` bits 2:0
+        self.write_mreg(
+            delay,
+            RegisterBank::MReg1,
+            &Mreg1::APEX_CONFIG10,
+            low_g.peak_threshold << 3 | low_g.time_threshold,
+        )
+    }
+
+    fn write_high_g(
+        &mut self,
+        delay: &mut impl DelayNs,
+        high_g: HighGSelectors,
+    ) -> Result<(), Error<E>> {
+        // `HIGHG_PEAK_TH_HYST_SEL` occupies bits 2:0 in the register
+        self.modify_mreg(
+            delay,
+            RegisterBank::MReg1,
+            &Mreg1::APEX_CONFIG5,
+            0b0000_0111,
+            high_g.hysteresis,
+        )?;
+        // `HIGHG_PEAK_TH_SEL` occupies bits 7:3 and `HIGHG_TIME_TH_SEL` bits 2:0
+        self.write_mreg(
+            delay,
+            RegisterBank::MReg1,
+            &Mreg1::APEX_CONFIG11,
+            high_g.peak_threshold << 3 | high_g.time_threshold,
+        )
+    }
+}
+
+/// Register selectors for the pedometer tuning parameters
+#[derive(Clone, Copy)]
+pub(crate) struct PedometerSelectors {
+    amp_threshold: u8,
+    step_count_threshold: u8,
+    step_detect_threshold: u8,
+    step_buffer_timeout: u8,
+    low_energy_amp_threshold: u8,
+}
+
+impl PedometerSelectors {
+    pub(crate) fn new(config: &PedometerConfig) -> Result<Self, SensorError> {
+        Ok(Self {
+            amp_threshold: selector(config.amp_threshold_mg.into(), 30, 4, 0xF)?,
+            step_count_threshold: selector(config.step_count_threshold.into(), 0, 1, 0xF)?,
+            step_detect_threshold: selector(config.step_detect_threshold.into(), 0, 1, 0x7)?,
+            step_buffer_timeout: selector(config.step_buffer_timeout.into(), 50, 25, 0x7)?,
+            low_energy_amp_threshold: selector(
+                config.low_energy_amp_threshold_mg.into(),
+                30,
+                5,
+                0xF,
+            )?,
+        })
+    }
+}
+
+/// Register selectors for the freefall detection parameters
+#[derive(Clone, Copy)]
+pub(crate) struct FreefallSelectors {
+    low_g: LowGSelectors,
+    high_g: HighGSelectors,
+    debounce: u8,
+}
+
+impl FreefallSelectors {
+    pub(crate) fn new(config: &FreefallConfig) -> Result<Self, SensorError> {
+        let debounce = match config.debounce_duration.0 {
+            0 => 0,
+            ms => selector(ms, 1250, 125, 0xE)? + 1,
+        };
+
+        Ok(Self {
+            low_g: LowGSelectors::new(&config.low_g)?,
+            high_g: HighGSelectors::new(&config.high_g)?,
+            debounce,
+        })
+    }
+}
+
+/// Register selectors for the low-g detection parameters
+#[derive(Clone, Copy)]
+struct LowGSelectors {
+    peak_threshold: u8,
+    hysteresis: u8,
+    time_threshold: u8,
+}
+
+impl LowGSelectors {
+    fn new(config: &LowGConfig) -> Result<Self, SensorError> {
+        Ok(Self {
+            peak_threshold: thirty_seconds_selector(config.peak_threshold_mg, 0x1F)?,
+            hysteresis: thirty_seconds_selector(config.hysteresis_mg, 0x7)?,
+            time_threshold: selector(config.time_threshold.into(), 1, 1, 0x7)?,
+        })
+    }
+}
+
+/// Register selectors for the high-g detection parameters
+#[derive(Clone, Copy)]
+struct HighGSelectors {
+    peak_threshold: u8,
+    hysteresis: u8,
+    time_threshold: u8,
+}
+
+impl HighGSelectors {
+    fn new(config: &HighGConfig) -> Result<Self, SensorError> {
+        Ok(Self {
+            peak_threshold: selector(config.peak_threshold_mg, 250, 250, 0x1F)?,
+            hysteresis: thirty_seconds_selector(config.hysteresis_mg, 0x7)?,
+            time_threshold: selector(config.time_threshold.into(), 1, 1, 0x7)?,
+        })
+    }
+}
+
+/// Convert a value in mg into the selector for a field with a resolution of
+/// 31.25 mg (1/32 g), where a selector of 0 corresponds to 31.25 mg. The value
+/// is rounded to the nearest step.
+fn thirty_seconds_selector(mg: u16, max: u8) -> Result<u8, SensorError> {
+    let steps = (mg as u32 * 4 + 62) / 125;
+    if steps == 0 || steps - 1 > max as u32 {
+        return Err(SensorError::ThresholdOutOfRange);
+    }
+
+    Ok((steps - 1) as u8)
+}
+
+/// Convert the selector of a field with a resolution of 31.25 mg back into mg,
+/// rounded to the nearest mg
+fn thirty_seconds_mg(selector: u8) -> u16 {
+    (((selector as u32 + 1) * 125 + 2) / 4) as u16
+}
+
+/// Convert a value expressed in physical units into the register selector
+/// `(value - base) / step`, ensuring that it is exactly representable and
+/// does not exceed `max`.
+fn selector(value: u16, base: u16, step: u16, max: u8) -> Result<u8, SensorError> {
+    let offset = value
+        .checked_sub(base)
+        .ok_or(SensorError::ThresholdOutOfRange)?;
+
+    if offset % step != 0 || offset / step > max as u16 {
+        return Err(SensorError::ThresholdOutOfRange);
+    }
+
+    Ok((offset / step) as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selector_exact() {
+        assert_eq!(selector(1000, 1000, 1000, 7).unwrap(), 0);
+        assert_eq!(selector(3000, 1000, 1000, 7).unwrap(), 2);
+        assert_eq!(selector(8000, 1000, 1000, 7).unwrap(), 7);
+    }
+
+    #[test]
+    fn selector_rejects_invalid() {
+        // Below the base
+        assert!(matches!(
+            selector(500, 1000, 1000, 7),
+            Err(SensorError::ThresholdOutOfRange)
+        ));
+        // Not a multiple of the step
+        assert!(matches!(
+            selector(1500, 1000, 1000, 7),
+            Err(SensorError::ThresholdOutOfRange)
+        ));
+        // Above the maximum
+        assert!(matches!(
+            selector(9000, 1000, 1000, 7),
+            Err(SensorError::ThresholdOutOfRange)
+        ));
+    }
+
+    #[test]
+    fn thirty_seconds_selector_rounds() {
+        // Selector 0 corresponds to 31.25 mg
+        assert_eq!(thirty_seconds_selector(31, 31).unwrap(), 0);
+        assert_eq!(thirty_seconds_selector(63, 31).unwrap(), 1);
+        assert_eq!(thirty_seconds_selector(563, 31).unwrap(), 17);
+        assert_eq!(thirty_seconds_selector(1000, 31).unwrap(), 31);
+    }
+
+    #[test]
+    fn thirty_seconds_mg_round_trip() {
+        for selector in 0..=31 {
+            let mg = thirty_seconds_mg(selector);
+            assert_eq!(thirty_seconds_selector(mg, 31).unwrap(), selector);
+        }
+    }
+
+    #[test]
+    fn thirty_seconds_selector_range() {
+        assert!(matches!(
+            thirty_seconds_selector(0, 31),
+            Err(SensorError::ThresholdOutOfRange)
+        ));
+        assert!(matches!(
+            thirty_seconds_selector(1100, 31),
+            Err(SensorError::ThresholdOutOfRange)
+        ));
+    }
+
+    #[test]
+    fn default_freefall_thresholds_are_valid() {
+        assert!(LowGSelectors::new(&LowGConfig::default()).is_ok());
+        assert!(HighGSelectors::new(&HighGConfig::default()).is_ok());
+    }
+
+    #[test]
+    fn default_freefall_distances_match_reset() {
+        // `APEX_CONFIG12` resets to `FF_MAX_DURATION_SEL` 5 and
+        // `FF_MIN_DURATION_SEL` 1
+        let config = FreefallConfig::default();
+        assert_eq!(config.max_distance as u8, 5);
+        assert_eq!(config.min_distance as u8, 1);
+    }
+
+    #[test]
+    fn apex_features_round_trip() {
+        for bits in 0..16u8 {
+            let bits = bits << 3;
+            assert_eq!(ApexFeatures::from_bits(bits).bits(), bits);
+        }
+        assert!(!ApexFeatures::from_bits(0b1000_0111).any());
+    }
+}
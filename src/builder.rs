@@ -0,0 +1,520 @@
+use core::fmt::Debug;
+
+use accelerometer::vector::F32x3;
+use embedded_hal::{delay::DelayNs, i2c::I2c};
+
+use crate::{
+    apex::{FreefallSelectors, PedometerSelectors},
+    config::{Bitfield, Idle, WomEnable},
+    error::SensorError,
+    pack_offset,
+    register::Bank0,
+    wom::wom_threshold,
+    AccLpAvg,
+    AccelDlpfBw,
+    AccelOdr,
+    AccelRange,
+    Address,
+    ApexFeatures,
+    DmpOdr,
+    Error,
+    FreefallConfig,
+    GyroLpFiltBw,
+    GyroOdr,
+    GyroRange,
+    Icm42670,
+    InterruptPin,
+    InterruptPinConfig,
+    InterruptSources,
+    PedometerConfig,
+    PowerMode,
+    SmdSensitivity,
+    TempDlpfBw,
+    TiltWaitTime,
+    WomConfig,
+    ACCEL_OFFSET_SCALE,
+    GYRO_OFFSET_SCALE,
+};
+
+/// Complete sensor configuration of the device, applied in a single step by
+/// [Icm42670::apply_config]
+///
+/// The default configuration uses the same ranges, accelerometer output data
+/// rate and power mode as [Icm42670::new]. [Icm42670::new] does not write any
+/// of the other settings, which keep their reset values; these are not
+/// necessarily the defaults used here (the Wake-on-Motion thresholds reset to
+/// 0 rather than 100 mg, for instance), so applying the default configuration
+/// may change the state of a freshly initialized device. The user offsets are
+/// cleared and no interrupts are routed.
+///
+/// The parameters of the APEX motion features are written by
+/// [Icm42670::apply_config], but only take effect once the DMP is next
+/// initialized. Which APEX features are enabled, and whether Wake-on-Motion is
+/// enabled, is state rather than configuration: it is captured by
+/// [Icm42670::read_config] and only brought back by
+/// [Icm42670::restore_config], which initializes the DMP as required.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Config {
+    /// Range of the accelerometer
+    pub accel_range: AccelRange,
+    /// Range of the gyroscope
+    pub gyro_range: GyroRange,
+    /// Output data rate of the accelerometer
+    pub accel_odr: AccelOdr,
+    /// Output data rate of the gyroscope
+    pub gyro_odr: GyroOdr,
+    /// Bandwidth of the accelerometer low pass filter
+    pub accel_dlpf: AccelDlpfBw,
+    /// Bandwidth of the gyroscope low pass filter
+    pub gyro_filter: GyroLpFiltBw,
+    /// Bandwidth of the temperature low pass filter
+    pub temp_dlpf: TempDlpfBw,
+    /// Averaging used by the accelerometer in low-power mode
+    pub accel_avg: AccLpAvg,
+    /// Power mode of the accelerometer and gyroscope
+    pub power_mode: PowerMode,
+    /// Electrical configuration of pin `INT1`
+    pub int1: InterruptPinConfig,
+    /// Electrical configuration of pin `INT2`
+    pub int2: InterruptPinConfig,
+    /// Interrupts routed to pin `INT1`
+    pub int1_sources: InterruptSources,
+    /// Interrupts routed to pin `INT2`
+    pub int2_sources: InterruptSources,
+    /// Wake-on-Motion thresholds and behaviour
+    pub wom: WomConfig,
+    /// Gyroscope user offsets, in deg/sec
+    pub gyro_offset: F32x3,
+    /// Accelerometer user offsets, in g
+    pub accel_offset: F32x3,
+    /// Output data rate at which the DMP runs the APEX motion features
+    pub dmp_odr: DmpOdr,
+    /// Pedometer tuning parameters
+    pub pedometer: PedometerConfig,
+    /// Freefall, low-g and high-g detection parameters
+    pub freefall: FreefallConfig,
+    /// Time for which the device must remain tilted before a tilt is reported
+    pub tilt_wait_time: TiltWaitTime,
+    /// Significant motion detection sensitivity
+    pub smd_sensitivity: SmdSensitivity,
+    /// APEX motion features which are enabled, only restored by
+    /// [Icm42670::restore_config]
+    pub apex_features: ApexFeatures,
+    /// Whether Wake-on-Motion is enabled, only restored by
+    /// [Icm42670::restore_config]
+    pub wom_enabled: bool,
+}
+
+impl Config {
+    /// Create a builder, starting from the default configuration
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder::default()
+    }
+
+    /// Check that every setting can be written, so that nothing is written if
+    /// any of them cannot
+    fn validate(&self) -> Result<(), SensorError> {
+        if !self.accel_odr.is_supported_in(self.power_mode) {
+            return Err(SensorError::UnsupportedOdrForPowerMode);
+        }
+
+        for offset in [self.gyro_offset.x, self.gyro_offset.y, self.gyro_offset.z] {
+            pack_offset(offset * GYRO_OFFSET_SCALE)?;
+        }
+        for offset in [
+            self.accel_offset.x,
+            self.accel_offset.y,
+            self.accel_offset.z,
+        ] {
+            pack_offset(offset * ACCEL_OFFSET_SCALE)?;
+        }
+
+        wom_threshold(self.wom.x_threshold_mg)?;
+        wom_threshold(self.wom.y_threshold_mg)?;
+        wom_threshold(self.wom.z_threshold_mg)?;
+
+        PedometerSelectors::new(&self.pedometer)?;
+        FreefallSelectors::new(&self.freefall)?;
+
+        Ok(())
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            accel_range: AccelRange::default(),
+            gyro_range: GyroRange::default(),
+            accel_odr: AccelOdr::default(),
+            gyro_odr: GyroOdr::default(),
+            accel_dlpf: AccelDlpfBw::default(),
+            gyro_filter: GyroLpFiltBw::default(),
+            temp_dlpf: TempDlpfBw::default(),
+            accel_avg: AccLpAvg::default(),
+            power_mode: PowerMode::SixAxisLowNoise,
+            int1: InterruptPinConfig::default(),
+            int2: InterruptPinConfig::default(),
+            int1_sources: InterruptSources::default(),
+            int2_sources: InterruptSources::default(),
+            wom: WomConfig::default(),
+            gyro_offset: F32x3::default(),
+            accel_offset: F32x3::default(),
+            dmp_odr: DmpOdr::default(),
+            pedometer: PedometerConfig::default(),
+            freefall: FreefallConfig::default(),
+            tilt_wait_time: TiltWaitTime::default(),
+            smd_sensitivity: SmdSensitivity::default(),
+            apex_features: ApexFeatures::default(),
+            wom_enabled: false,
+        }
+    }
+}
+
+/// Builder for a [Config]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ConfigBuilder {
+    config: Config,
+}
+
+impl ConfigBuilder {
+    /// Set the range of the accelerometer
+    pub fn accel_range(mut self, range: AccelRange) -> Self {
+        self.config.accel_range = range;
+        self
+    }
+
+    /// Set the range of the gyroscope
+    pub fn gyro_range(mut self, range: GyroRange) -> Self {
+        self.config.gyro_range = range;
+        self
+    }
+
+    /// Set the output data rate of the accelerometer
+    pub fn accel_odr(mut self, odr: AccelOdr) -> Self {
+        self.config.accel_odr = odr;
+        self
+    }
+
+    /// Set the output data rate of the gyroscope
+    pub fn gyro_odr(mut self, odr: GyroOdr) -> Self {
+        self.config.gyro_odr = odr;
+        self
+    }
+
+    /// Set the bandwidth of the accelerometer low pass filter
+    pub fn accel_dlpf(mut self, dlpf: AccelDlpfBw) -> Self {
+        self.config.accel_dlpf = dlpf;
+        self
+    }
+
+    /// Set the bandwidth of the gyroscope low pass filter
+    pub fn gyro_filter(mut self, freq: GyroLpFiltBw) -> Self {
+        self.config.gyro_filter = freq;
+        self
+    }
+
+    /// Set the bandwidth of the temperature low pass filter
+    pub fn temp_dlpf(mut self, freq: TempDlpfBw) -> Self {
+        self.config.temp_dlpf = freq;
+        self
+    }
+
+    /// Set the averaging used by the accelerometer in low-power mode
+    pub fn accel_avg(mut self, avg: AccLpAvg) -> Self {
+        self.config.accel_avg = avg;
+        self
+    }
+
+    /// Set the power mode of the accelerometer and gyroscope
+    pub fn power_mode(mut self, mode: PowerMode) -> Self {
+        self.config.power_mode = mode;
+        self
+    }
+
+    /// Set the electrical configuration of the given interrupt pin
+    pub fn interrupt_pin(mut self, pin: InterruptPin, config: InterruptPinConfig) -> Self {
+        match pin {
+            InterruptPin::Int1 => self.config.int1 = config,
+            InterruptPin::Int2 => self.config.int2 = config,
+        }
+        self
+    }
+
+    /// Set the interrupts routed to the given interrupt pin
+    pub fn interrupt_sources(mut self, pin: InterruptPin, sources: InterruptSources) -> Self {
+        match pin {
+            InterruptPin::Int1 => self.config.int1_sources = sources,
+            InterruptPin::Int2 => self.config.int2_sources = sources,
+        }
+        self
+    }
+
+    /// Set the Wake-on-Motion thresholds and behaviour
+    pub fn wom(mut self, config: WomConfig) -> Self {
+        self.config.wom = config;
+        self
+    }
+
+    /// Set the gyroscope user offsets, in deg/sec
+    pub fn gyro_offset(mut self, offset: F32x3) -> Self {
+        self.config.gyro_offset = offset;
+        self
+    }
+
+    /// Set the accelerometer user offsets, in g
+    pub fn accel_offset(mut self, offset: F32x3) -> Self {
+        self.config.accel_offset = offset;
+        self
+    }
+
+    /// Set the output data rate at which the DMP runs the APEX motion features
+    pub fn dmp_odr(mut self, odr: DmpOdr) -> Self {
+        self.config.dmp_odr = odr;
+        self
+    }
+
+    /// Set the pedometer tuning parameters
+    pub fn pedometer(mut self, config: PedometerConfig) -> Self {
+        self.config.pedometer = config;
+        self
+    }
+
+    /// Set the freefall, low-g and high-g detection parameters
+    pub fn freefall(mut self, config: FreefallConfig) -> Self {
+        self.config.freefall = config;
+        self
+    }
+
+    /// Set the time for which the device must remain tilted before a tilt is
+    /// reported
+    pub fn tilt_wait_time(mut self, wait: TiltWaitTime) -> Self {
+        self.config.tilt_wait_time = wait;
+        self
+    }
+
+    /// Set the significant motion detection sensitivity
+    pub fn smd_sensitivity(mut self, sensitivity: SmdSensitivity) -> Self {
+        self.config.smd_sensitivity = sensitivity;
+        self
+    }
+
+    /// Return the finished configuration
+    pub fn build(self) -> Config {
+        self.config
+    }
+}
+
+impl<I2C, E> Icm42670<I2C>
+where
+    I2C: I2c<Error = E>,
+    E: Debug,
+{
+    /// Instantiate a new instance of the driver and initialize the device with
+    /// the given configuration
+    ///
+    /// Unlike [Self::new], the device is not first initialized with the default
+    /// configuration; only the device ID is verified before the configuration
+    /// is applied.
+    pub fn new_with_config(
+        i2c: I2C,
+        address: Address,
+        delay: &mut impl DelayNs,
+        config: &Config,
+    ) -> Result<Self, Error<E>> {
+        let mut me = Self::attach(i2c, address)?;
+        me.apply_config(delay, config)?;
+
+        Ok(me)
+    }
+
+    /// Apply a complete sensor configuration
+    ///
+    /// The sensors are first turned off, as some fields (such as the
+    /// low-power averaging) cannot be changed while they are running. The
+    /// configuration registers are then written before finally entering the
+    /// requested power mode.
+    ///
+    /// The APEX parameters and DMP output data rate are written, but the DMP is
+    /// not initialized and the enabled APEX features and Wake-on-Motion are
+    /// left as they are; see [Self::restore_config].
+    ///
+    /// The whole configuration is validated before anything is written. If
+    /// the accelerometer output data rate cannot be used in the requested power
+    /// mode, [SensorError::UnsupportedOdrForPowerMode] is returned; if a user
+    /// offset, Wake-on-Motion threshold or APEX parameter is out of range,
+    /// [SensorError::OffsetOutOfRange] or [SensorError::ThresholdOutOfRange]
+    /// is returned.
+    pub fn apply_config(
+        &mut self,
+        delay: &mut impl DelayNs,
+        config: &Config,
+    ) -> Result<(), Error<E>> {
+        config.validate().map_err(Error::SensorError)?;
+
+        self.set_power_mode(PowerMode::Sleep)?;
+        // No register writes are permitted for 200µs after changing power mode.
+        delay.delay_us(200);
+
+        self.set_interrupt_pin_config(InterruptPin::Int1, config.int1)?;
+        self.set_interrupt_pin_config(InterruptPin::Int2, config.int2)?;
+
+        self.update_reg(config.gyro_range)?;
+        self.update_reg(config.gyro_odr)?;
+        self.update_reg(config.accel_range)?;
+        self.update_reg(config.accel_odr)?;
+        self.update_reg(config.temp_dlpf)?;
+        self.update_reg(config.gyro_filter)?;
+        self.update_reg(config.accel_avg)?;
+        self.update_reg(config.accel_dlpf)?;
+        self.update_reg(config.dmp_odr)?;
+
+        self.with_clock_running(delay, |imu, delay| {
+            imu.set_gyro_offset(delay, config.gyro_offset)?;
+            imu.set_accel_offset(delay, config.accel_offset)?;
+            imu.configure_wom(delay, &config.wom)?;
+            imu.set_interrupt_sources(delay, InterruptPin::Int1, &config.int1_sources)?;
+            imu.set_interrupt_sources(delay, InterruptPin::Int2, &config.int2_sources)?;
+            imu.set_pedometer_config(delay, &config.pedometer)?;
+            imu.set_freefall_config(delay, &config.freefall)?;
+            imu.set_tilt_wait_time(delay, config.tilt_wait_time)?;
+            imu.set_smd_sensitivity(delay, config.smd_sensitivity)
+        })?;
+
+        self.set_power_mode(config.power_mode)?;
+        delay.delay_us(200);
+
+        // The gyroscope requires up to 45ms to start up.
+        if config.power_mode.gyro_low_noise() {
+            delay.delay_ms(45);
+        }
+
+        Ok(())
+    }
+
+    /// Read back the complete sensor configuration of the device
+    ///
+    /// The result can be logged alongside sensor data, or passed to
+    /// [Self::restore_config] to re-apply it after a reset.
+    pub fn read_config(&mut self, delay: &mut impl DelayNs) -> Result<Config, Error<E>> {
+        let mreg = self.with_clock_running(delay, |imu, delay| {
+            Ok((
+                imu.gyro_offset(delay)?,
+                imu.accel_offset(delay)?,
+                imu.wom_config(delay)?,
+                imu.interrupt_sources(delay, InterruptPin::Int1)?,
+                imu.interrupt_sources(delay, InterruptPin::Int2)?,
+                imu.pedometer_config(delay)?,
+                imu.freefall_config(delay)?,
+                imu.tilt_wait_time(delay)?,
+                imu.smd_sensitivity(delay)?,
+            ))
+        })?;
+        let (
+            gyro_offset,
+            accel_offset,
+            wom,
+            int1_sources,
+            int2_sources,
+            pedometer,
+            freefall,
+            tilt_wait_time,
+            smd_sensitivity,
+        ) = mreg;
+
+        Ok(Config {
+            accel_range: self.accel_range()?,
+            gyro_range: self.gyro_range()?,
+            accel_odr: self.accel_odr()?,
+            gyro_odr: self.gyro_odr()?,
+            accel_dlpf: self.accel_dlpf_bw()?,
+            gyro_filter: self.gyro_lp_filter_bandwidth()?,
+            temp_dlpf: self.temp_dlpf()?,
+            accel_avg: self.accel_low_power_avg()?,
+            power_mode: self.power_mode()?,
+            int1: self.interrupt_pin_config(InterruptPin::Int1)?,
+            int2: self.interrupt_pin_config(InterruptPin::Int2)?,
+            int1_sources,
+            int2_sources,
+            wom,
+            gyro_offset,
+            accel_offset,
+            dmp_odr: self.apex_odr()?,
+            pedometer,
+            freefall,
+            tilt_wait_time,
+            smd_sensitivity,
+            apex_features: self.apex_features()?,
+            wom_enabled: self.read_reg(&Bank0::WOM_CONFIG)? & WomEnable::BITMASK != 0,
+        })
+    }
+
+    /// Re-apply a configuration previously returned by [Self::read_config],
+    /// e.g. after a soft reset or brown-out
+    ///
+    /// The configuration is applied as by [Self::apply_config], after which
+    /// Wake-on-Motion is enabled or disabled as captured. If any APEX feature
+    /// was enabled the DMP is then initialized, so that it loads the restored
+    /// parameters, and exactly the captured features are enabled again. Note
+    /// that this resets the pedometer step count.
+    ///
+    /// If an APEX feature is enabled but the accelerometer would not be running
+    /// at the output data rate required by the DMP,
+    /// [SensorError::UnsupportedApexOdr] is returned without writing anything.
+    pub fn restore_config(
+        &mut self,
+        delay: &mut impl DelayNs,
+        config: &Config,
+    ) -> Result<(), Error<E>> {
+        if config.apex_features.any() {
+            let mode = config.power_mode;
+            let accel_running = mode.accel_low_power() || mode.accel_low_noise();
+            if !accel_running || config.accel_odr != config.dmp_odr.accel_odr() {
+                return Err(Error::SensorError(SensorError::UnsupportedApexOdr));
+            }
+        }
+
+        self.apply_config(delay, config)?;
+
+        if config.wom_enabled {
+            self.enable_wom()?;
+        } else {
+            self.disable_wom()?;
+        }
+
+        if config.apex_features.any() {
+            self.init_apex(delay, config.dmp_odr)?;
+        }
+
+        self.set_apex_features(config.apex_features)
+    }
+
+    /// Run `f` with the internal oscillator forced on, as the MREG registers
+    /// cannot be accessed while both sensors are off.
+    fn with_clock_running<D, T>(
+        &mut self,
+        delay: &mut D,
+        f: impl FnOnce(&mut Self, &mut D) -> Result<T, Error<E>>,
+    ) -> Result<T, Error<E>>
+    where
+        D: DelayNs,
+    {
+        let was_idle = self.read_reg(&Bank0::PWR_MGMT0)? & Idle::BITMASK != 0;
+
+        self.update_reg(Idle::Enabled)?;
+        delay.delay_us(200);
+
+        let result = f(self, delay);
+
+        // Always attempt to restore the previous state, even if `f` failed.
+        let restored = if was_idle {
+            Ok(())
+        } else {
+            self.update_reg(Idle::Disabled)
+        };
+
+        let result = result?;
+        restored?;
+
+        Ok(result)
+    }
+}
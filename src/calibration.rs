@@ -0,0 +1,840 @@
+use core::fmt::Debug;
+
+use accelerometer::vector::F32x3;
+use embedded_hal::{delay::DelayNs, i2c::I2c};
+
+use crate::{error::SensorError, Error, Icm42670};
+
+/// Maximum per-axis variance of the gyroscope output, in (deg/sec)², for which
+/// the device is still considered to be stationary
+pub const GYRO_STATIONARY_VARIANCE: f32 = 0.25;
+
+/// Maximum per-axis variance of the accelerometer output, in g², for which the
+/// device is still considered to be stationary
+pub const ACCEL_STATIONARY_VARIANCE: f32 = 0.0004;
+
+impl<I2C, E> Icm42670<I2C>
+where
+    I2C: I2c<Error = E>,
+    E: Debug,
+{
+    /// Estimate the gyroscope bias, in deg/sec, by averaging `samples` readings
+    /// while the device is stationary
+    ///
+    /// The returned bias is relative to the current gyro output, so it already
+    /// accounts for any user offset which is presently programmed. When
+    /// `program_offset` is `true` the user offset registers are updated so that
+    /// the bias is subsequently removed by the device itself.
+    ///
+    /// The gyroscope must be running in low-noise mode, otherwise
+    /// [SensorError::GyroNotRunning] is returned. If the variance of any axis
+    /// exceeds [GYRO_STATIONARY_VARIANCE] the device was moved during
+    /// calibration, and an error is returned without programming anything.
+    pub fn calibrate_gyro_bias(
+        &mut self,
+        delay: &mut impl DelayNs,
+        samples: u16,
+        program_offset: bool,
+    ) -> Result<F32x3, Error<E>> {
+        if !self.power_mode.gyro_low_noise() {
+            return Err(Error::SensorError(SensorError::GyroNotRunning));
+        }
+        if samples < 2 {
+            return Err(Error::SensorError(SensorError::NotEnoughSamples));
+        }
+
+        let (_, stats) = self.sample_stats(delay, samples)?;
+
+        if stats.max_variance() > GYRO_STATIONARY_VARIANCE {
+            return Err(Error::SensorError(SensorError::NotStationary));
+        }
+
+        let bias = stats.mean();
+        if program_offset {
+            let offset = self.gyro_offset(delay)?;
+            let offset = F32x3::new(offset.x - bias.x, offset.y - bias.y, offset.z - bias.z);
+
+            self.set_gyro_offset(delay, offset)?;
+        }
+
+        Ok(bias)
+    }
+
+    /// Average `samples` accelerometer readings while the device is held
+    /// stationary in one of the six gravity-aligned orientations, and record
+    /// the result in the provided calibrator
+    ///
+    /// Returns the orientation which was detected, so that the caller can
+    /// prompt for the next one. If the device was moved while sampling, or is
+    /// not resting close enough to one of the six orientations, an error is
+    /// returned and the calibrator is left unchanged.
+    pub fn collect_accel_position(
+        &mut self,
+        delay: &mut impl DelayNs,
+        samples: u16,
+        calibrator: &mut SixPositionCalibrator,
+    ) -> Result<Face, Error<E>> {
+        if samples < 2 {
+            return Err(Error::SensorError(SensorError::NotEnoughSamples));
+        }
+
+        let (stats, _) = self.sample_stats(delay, samples)?;
+
+        if stats.max_variance() > ACCEL_STATIONARY_VARIANCE {
+            return Err(Error::SensorError(SensorError::NotStationary));
+        }
+
+        let face = calibrator.add_reading(stats.mean())?;
+
+        Ok(face)
+    }
+
+    /// Collect running statistics of `samples` accelerometer and gyroscope
+    /// readings, in g and deg/sec respectively, without any calibration or
+    /// compensation applied
+    ///
+    /// Readings are taken at the output data rate of the slower sensor.
+    pub(crate) fn sample_stats(
+        &mut self,
+        delay: &mut impl DelayNs,
+        samples: u16,
+    ) -> Result<(SampleStats, SampleStats), Error<E>> {
+        let accel_scale = self.accel_range()?.scale_factor();
+        let gyro_scale = self.gyro_range()?.scale_factor();
+        let odr = self.accel_odr()?.as_f32().min(self.gyro_odr()?.as_f32());
+        let period = sample_period_us(odr);
+
+        let mut accel_stats = SampleStats::default();
+        let mut gyro_stats = SampleStats::default();
+        for _ in 0..samples {
+            delay.delay_us(period);
+
+            let raw = self.read_accel_raw()?;
+            accel_stats.push(F32x3::new(
+                raw.x as f32 / accel_scale,
+                raw.y as f32 / accel_scale,
+                raw.z as f32 / accel_scale,
+            ));
+
+            let raw = self.gyro_raw()?;
+            gyro_stats.push(F32x3::new(
+                raw.x as f32 / gyro_scale,
+                raw.y as f32 / gyro_scale,
+                raw.z as f32 / gyro_scale,
+            ));
+        }
+
+        Ok((accel_stats, gyro_stats))
+    }
+
+    /// Return the calibration currently applied to the normalized gyro and
+    /// accelerometer data
+    pub fn calibration(&self) -> &CalibrationData {
+        &self.calibration
+    }
+
+    /// Set the calibration to apply to the normalized gyro and accelerometer
+    /// data
+    pub fn set_calibration(&mut self, calibration: CalibrationData) {
+        self.calibration = calibration;
+    }
+
+    /// Decode a calibration blob previously produced by
+    /// [CalibrationData::to_bytes] and apply it
+    ///
+    /// If the blob fails to decode the current calibration is left unchanged.
+    pub fn load_calibration(&mut self, bytes: &[u8]) -> Result<(), Error<E>> {
+        let calibration = CalibrationData::from_bytes(bytes)?;
+        self.set_calibration(calibration);
+
+        Ok(())
+    }
+
+    /// Return the normalized gyro data with the residual bias estimated by the
+    /// given tracker removed
+    ///
+    /// The accelerometer is sampled alongside the gyroscope so that the
+    /// tracker is able to detect stationary periods, during which it refines
+    /// its bias estimate.
+    pub fn gyro_norm_tracked(&mut self, tracker: &mut GyroBiasTracker) -> Result<F32x3, Error<E>> {
+        let accel = self.read_accel_norm()?;
+        let gyro = self.gyro_norm()?;
+
+        Ok(tracker.update(accel, gyro))
+    }
+}
+
+/// Gravity-aligned orientations used by the six-position accelerometer
+/// calibration, named by the axis which is pointing up (away from the ground)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Face {
+    /// +X pointing up, the X axis reads +1 g
+    XUp   = 0,
+    /// +X pointing down, the X axis reads -1 g
+    XDown = 1,
+    /// +Y pointing up, the Y axis reads +1 g
+    YUp   = 2,
+    /// +Y pointing down, the Y axis reads -1 g
+    YDown = 3,
+    /// +Z pointing up, the Z axis reads +1 g
+    ZUp   = 4,
+    /// +Z pointing down, the Z axis reads -1 g
+    ZDown = 5,
+}
+
+impl Face {
+    /// All six orientations, in the order they are stored by the calibrator
+    pub const ALL: [Face; 6] = [
+        Face::XUp,
+        Face::XDown,
+        Face::YUp,
+        Face::YDown,
+        Face::ZUp,
+        Face::ZDown,
+    ];
+
+    /// Determine the orientation from an averaged accelerometer reading in g
+    ///
+    /// Returns `None` if the magnitude of the reading is not close to 1 g, or
+    /// if no single axis is clearly aligned with gravity.
+    pub fn detect(accel: F32x3) -> Option<Self> {
+        let (ax, ay, az) = (abs(accel.x), abs(accel.y), abs(accel.z));
+        let (axis, value, dominant) = if ax >= ay && ax >= az {
+            (0, accel.x, ax)
+        } else if ay >= az {
+            (1, accel.y, ay)
+        } else {
+            (2, accel.z, az)
+        };
+
+        // Only gravity should be acting on the device, so the magnitude must lie
+        // between 0.8 g and 1.2 g. This also rejects readings from a sensor which
+        // is not running. Magnitudes are compared as squares throughout to avoid
+        // a square root.
+        let magnitude_sq = accel.x * accel.x + accel.y * accel.y + accel.z * accel.z;
+        if !(0.64..=1.44).contains(&magnitude_sq) {
+            return None;
+        }
+
+        // Require the dominant axis to carry at least 90% of the magnitude, which
+        // corresponds to a misalignment of roughly 25 degrees.
+        if dominant * dominant < 0.81 * magnitude_sq {
+            return None;
+        }
+
+        let index = axis * 2 + if value >= 0.0 { 0 } else { 1 };
+
+        Some(Face::ALL[index])
+    }
+}
+
+/// Per-axis accelerometer bias and scale correction
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AccelCalibration {
+    /// Bias of each axis, in g
+    pub bias: F32x3,
+    /// Scale correction applied to each axis after removing the bias
+    pub scale: F32x3,
+}
+
+impl AccelCalibration {
+    /// Correct a normalized accelerometer reading
+    pub fn apply(&self, accel: F32x3) -> F32x3 {
+        F32x3::new(
+            (accel.x - self.bias.x) * self.scale.x,
+            (accel.y - self.bias.y) * self.scale.y,
+            (accel.z - self.bias.z) * self.scale.z,
+        )
+    }
+}
+
+impl Default for AccelCalibration {
+    fn default() -> Self {
+        Self {
+            bias: F32x3::new(0.0, 0.0, 0.0),
+            scale: F32x3::new(1.0, 1.0, 1.0),
+        }
+    }
+}
+
+/// Guided six-position accelerometer calibration
+///
+/// Averaged readings are collected with the device resting in each of the six
+/// orientations described by [Face], in any order. Once all six have been
+/// collected the per-axis bias and scale can be solved for, as each axis will
+/// have been measured at both +1 g and -1 g.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SixPositionCalibrator {
+    readings: [Option<F32x3>; 6],
+}
+
+impl SixPositionCalibrator {
+    /// Create a new calibrator with no readings collected
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an averaged accelerometer reading in g, replacing any earlier
+    /// reading for the same orientation
+    pub fn add_reading(&mut self, accel: F32x3) -> Result<Face, SensorError> {
+        let face = Face::detect(accel).ok_or(SensorError::AmbiguousOrientation)?;
+        self.readings[face as usize] = Some(accel);
+
+        Ok(face)
+    }
+
+    /// Has a reading been collected for the given orientation?
+    pub fn is_collected(&self, face: Face) -> bool {
+        self.readings[face as usize].is_some()
+    }
+
+    /// The first orientation for which no reading has been collected yet
+    pub fn next_missing(&self) -> Option<Face> {
+        Face::ALL.into_iter().find(|face| !self.is_collected(*face))
+    }
+
+    /// Have readings been collected for all six orientations?
+    pub fn is_complete(&self) -> bool {
+        self.next_missing().is_none()
+    }
+
+    /// Solve for the per-axis bias and scale, or return `None` if any
+    /// orientation has not been collected yet
+    pub fn solve(&self) -> Option<AccelCalibration> {
+        let mut bias = [0.0; 3];
+        let mut scale = [1.0; 3];
+
+        for axis in 0..3 {
+            let up = self.readings[axis * 2]?[axis];
+            let down = self.readings[axis * 2 + 1]?[axis];
+
+            // With a bias `b` and gain `k` the axis reads `b + k` pointing up and
+            // `b - k` pointing down.
+            bias[axis] = (up + down) / 2.0;
+            scale[axis] = 2.0 / (up - down);
+        }
+
+        Some(AccelCalibration {
+            bias: F32x3::new(bias[0], bias[1], bias[2]),
+            scale: F32x3::new(scale[0], scale[1], scale[2]),
+        })
+    }
+}
+
+/// Online gyroscope bias tracker
+///
+/// Samples are grouped into windows of a fixed length. At the end of each
+/// window, if the variance of both the accelerometer and the gyroscope was
+/// below their respective thresholds the device is considered stationary, and
+/// the bias estimate is moved towards the mean gyro output of the window by a
+/// fraction given by the tracker's gain. This allows slow drift, for example
+/// due to temperature, to be followed during long runs.
+#[derive(Clone, Copy, Debug)]
+pub struct GyroBiasTracker {
+    window: u16,
+    gain: f32,
+    accel_variance: f32,
+    gyro_variance: f32,
+    accel_stats: SampleStats,
+    gyro_stats: SampleStats,
+    stationary: bool,
+    bias: F32x3,
+    confidence: f32,
+}
+
+impl GyroBiasTracker {
+    /// Create a new tracker with the given window length, in samples, and
+    /// gain in the range `(0, 1]`
+    ///
+    /// The window is clamped to at least 2 samples, and the gain to the range
+    /// `(0, 1]`; a gain which is not positive (including NaN) is replaced by
+    /// the smallest step which still moves the estimate. The stationary
+    /// thresholds default to [ACCEL_STATIONARY_VARIANCE] and
+    /// [GYRO_STATIONARY_VARIANCE].
+    pub fn new(window: u16, gain: f32) -> Self {
+        let gain = if gain > 1.0 {
+            1.0
+        } else if gain > 0.0 {
+            gain
+        } else {
+            f32::EPSILON
+        };
+
+        Self {
+            window: window.max(2),
+            gain,
+            accel_variance: ACCEL_STATIONARY_VARIANCE,
+            gyro_variance: GYRO_STATIONARY_VARIANCE,
+            accel_stats: SampleStats::default(),
+            gyro_stats: SampleStats::default(),
+            stationary: false,
+            bias: F32x3::default(),
+            confidence: 0.0,
+        }
+    }
+
+    /// Override the per-axis variance thresholds, in g² and (deg/sec)²
+    /// respectively, below which the device is considered stationary
+    pub fn with_thresholds(mut self, accel_variance: f32, gyro_variance: f32) -> Self {
+        self.accel_variance = accel_variance;
+        self.gyro_variance = gyro_variance;
+        self
+    }
+
+    /// Feed a normalized accelerometer (g) and gyroscope (deg/sec) sample into
+    /// the tracker, returning the gyro sample with the estimated bias removed
+    pub fn update(&mut self, accel: F32x3, gyro: F32x3) -> F32x3 {
+        self.accel_stats.push(accel);
+        self.gyro_stats.push(gyro);
+
+        if self.gyro_stats.count() >= self.window as u32 {
+            self.stationary = self.accel_stats.max_variance() <= self.accel_variance
+                && self.gyro_stats.max_variance() <= self.gyro_variance;
+
+            if self.stationary {
+                let mean = self.gyro_stats.mean();
+
+                self.bias.x += self.gain * (mean.x - self.bias.x);
+                self.bias.y += self.gain * (mean.y - self.bias.y);
+                self.bias.z += self.gain * (mean.z - self.bias.z);
+
+                self.confidence += self.gain * (1.0 - self.confidence);
+            }
+
+            self.accel_stats = SampleStats::default();
+            self.gyro_stats = SampleStats::default();
+        }
+
+        F32x3::new(
+            gyro.x - self.bias.x,
+            gyro.y - self.bias.y,
+            gyro.z - self.bias.z,
+        )
+    }
+
+    /// Current bias estimate, in deg/sec
+    pub fn bias(&self) -> F32x3 {
+        self.bias
+    }
+
+    /// Confidence in the current bias estimate, in the range `[0, 1]`
+    ///
+    /// This is the fraction of the estimate which is derived from observed
+    /// stationary windows rather than from the initial estimate, and so grows
+    /// towards 1 as more stationary periods are seen.
+    pub fn confidence(&self) -> f32 {
+        self.confidence
+    }
+
+    /// Was the device stationary during the most recently completed window?
+    pub fn is_stationary(&self) -> bool {
+        self.stationary
+    }
+
+    /// Seed the tracker with a known bias, in deg/sec, and the confidence in it
+    pub fn set_bias(&mut self, bias: F32x3, confidence: f32) {
+        self.bias = bias;
+        self.confidence = confidence;
+    }
+}
+
+impl Default for GyroBiasTracker {
+    fn default() -> Self {
+        Self::new(100, 0.05)
+    }
+}
+
+/// Complete calibration for both sensors, which can be serialized to a
+/// fixed-size binary blob for persistent storage
+///
+/// Corrections are applied in software to the normalized sensor data: the
+/// bias (and for the accelerometer, scale) is corrected first, and the result
+/// is then multiplied by the misalignment matrix.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CalibrationData {
+    /// Gyroscope bias, in deg/sec
+    pub gyro_bias: F32x3,
+    /// Accelerometer bias and scale correction
+    pub accel: AccelCalibration,
+    /// Row-major matrix mapping the sensor axes onto the body axes
+    pub misalignment: [[f32; 3]; 3],
+}
+
+impl CalibrationData {
+    /// Current version of the binary format
+    pub const VERSION: u8 = 1;
+
+    /// Size of the binary format, in bytes
+    ///
+    /// The blob consists of a 2 byte magic number, a 1 byte version, 1 reserved
+    /// byte, 18 little-endian `f32` values (gyro bias, accel bias, accel scale
+    /// and the misalignment matrix, in that order) and finally a little-endian
+    /// CRC-32 of all preceding bytes.
+    pub const SIZE: usize = 4 + 18 * 4 + 4;
+
+    /// Magic number identifying a calibration blob
+    const MAGIC: [u8; 2] = *b"IC";
+
+    /// Apply the bias correction and misalignment matrix to a normalized
+    /// gyroscope reading
+    pub fn correct_gyro(&self, gyro: F32x3) -> F32x3 {
+        let unbiased = F32x3::new(
+            gyro.x - self.gyro_bias.x,
+            gyro.y - self.gyro_bias.y,
+            gyro.z - self.gyro_bias.z,
+        );
+
+        self.align(unbiased)
+    }
+
+    /// Apply the bias and scale correction and misalignment matrix to a
+    /// normalized accelerometer reading
+    pub fn correct_accel(&self, accel: F32x3) -> F32x3 {
+        self.align(self.accel.apply(accel))
+    }
+
+    /// Serialize the calibration into its binary format
+    pub fn to_bytes(&self) -> [u8; Self::SIZE] {
+        let mut bytes = [0u8; Self::SIZE];
+        bytes[0..2].copy_from_slice(&Self::MAGIC);
+        bytes[2] = Self::VERSION;
+
+        for (i, value) in self.values().iter().enumerate() {
+            let offset = 4 + i * 4;
+            bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        }
+
+        let crc = crc32(&bytes[..Self::SIZE - 4]);
+        bytes[Self::SIZE - 4..].copy_from_slice(&crc.to_le_bytes());
+
+        bytes
+    }
+
+    /// Deserialize a calibration from its binary format, verifying its
+    /// integrity
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SensorError> {
+        if bytes.len() != Self::SIZE || bytes[0..2] != Self::MAGIC || bytes[2] != Self::VERSION {
+            return Err(SensorError::BadCalibrationFormat);
+        }
+
+        let mut crc = [0u8; 4];
+        crc.copy_from_slice(&bytes[Self::SIZE - 4..]);
+        if crc32(&bytes[..Self::SIZE - 4]) != u32::from_le_bytes(crc) {
+            return Err(SensorError::BadCalibrationChecksum);
+        }
+
+        let mut values = [0.0; 18];
+        for (i, value) in values.iter_mut().enumerate() {
+            let offset = 4 + i * 4;
+            let mut raw = [0u8; 4];
+            raw.copy_from_slice(&bytes[offset..offset + 4]);
+            *value = f32::from_le_bytes(raw);
+        }
+
+        let v = values;
+        Ok(Self {
+            gyro_bias: F32x3::new(v[0], v[1], v[2]),
+            accel: AccelCalibration {
+                bias: F32x3::new(v[3], v[4], v[5]),
+                scale: F32x3::new(v[6], v[7], v[8]),
+            },
+            misalignment: [
+                [v[9], v[10], v[11]],
+                [v[12], v[13], v[14]],
+                [v[15], v[16], v[17]],
+            ],
+        })
+    }
+
+    /// All values in the order in which they are serialized
+    fn values(&self) -> [f32; 18] {
+        let (g, a, m) = (self.gyro_bias, self.accel, self.misalignment);
+
+        [
+            g.x, g.y, g.z, a.bias.x, a.bias.y, a.bias.z, a.scale.x, a.scale.y, a.scale.z, m[0][0],
+            m[0][1], m[0][2], m[1][0], m[1][1], m[1][2], m[2][0], m[2][1], m[2][2],
+        ]
+    }
+
+    /// Multiply a vector by the misalignment matrix
+    fn align(&self, v: F32x3) -> F32x3 {
+        let m = &self.misalignment;
+
+        F32x3::new(
+            m[0][0] * v.x + m[0][1] * v.y + m[0][2] * v.z,
+            m[1][0] * v.x + m[1][1] * v.y + m[1][2] * v.z,
+            m[2][0] * v.x + m[2][1] * v.y + m[2][2] * v.z,
+        )
+    }
+}
+
+impl Default for CalibrationData {
+    fn default() -> Self {
+        Self {
+            gyro_bias: F32x3::new(0.0, 0.0, 0.0),
+            accel: AccelCalibration::default(),
+            misalignment: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+        }
+    }
+}
+
+/// Running mean and variance of a three-axis signal
+///
+/// Uses Welford's algorithm, which remains numerically stable in single
+/// precision even for long runs of samples.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct SampleStats {
+    count: u32,
+    mean: F32x3,
+    m2: F32x3,
+}
+
+impl SampleStats {
+    /// Add a sample to the running statistics
+    pub fn push(&mut self, sample: F32x3) {
+        self.count += 1;
+        let n = self.count as f32;
+
+        let dx = sample.x - self.mean.x;
+        let dy = sample.y - self.mean.y;
+        let dz = sample.z - self.mean.z;
+
+        self.mean.x += dx / n;
+        self.mean.y += dy / n;
+        self.mean.z += dz / n;
+
+        self.m2.x += dx * (sample.x - self.mean.x);
+        self.m2.y += dy * (sample.y - self.mean.y);
+        self.m2.z += dz * (sample.z - self.mean.z);
+    }
+
+    /// Number of samples pushed so far
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// Mean of all samples pushed so far
+    pub fn mean(&self) -> F32x3 {
+        self.mean
+    }
+
+    /// Sample variance of each axis
+    pub fn variance(&self) -> F32x3 {
+        if self.count < 2 {
+            return F32x3::default();
+        }
+
+        let n = (self.count - 1) as f32;
+
+        F32x3::new(self.m2.x / n, self.m2.y / n, self.m2.z / n)
+    }
+
+    /// Largest sample variance of any axis
+    pub fn max_variance(&self) -> f32 {
+        let var = self.variance();
+
+        var.x.max(var.y).max(var.z)
+    }
+}
+
+/// Time between consecutive samples at the given output data rate, in
+/// microseconds
+pub(crate) fn sample_period_us(odr: f32) -> u32 {
+    (1_000_000.0 / odr) as u32
+}
+
+/// Absolute value of a float, which is not available in `core`
+pub(crate) fn abs(value: f32) -> f32 {
+    if value < 0.0 {
+        -value
+    } else {
+        value
+    }
+}
+
+/// CRC-32 (IEEE 802.3) of the given bytes
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f32, b: f32) {
+        assert!(abs(a - b) < 1e-5, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(&[]), 0);
+    }
+
+    #[test]
+    fn calibration_round_trip() {
+        let calibration = CalibrationData {
+            gyro_bias: F32x3::new(0.5, -1.25, 2.0),
+            accel: AccelCalibration {
+                bias: F32x3::new(0.01, -0.02, 0.03),
+                scale: F32x3::new(1.01, 0.99, 1.02),
+            },
+            misalignment: [[1.0, 0.01, 0.0], [-0.01, 1.0, 0.02], [0.0, -0.02, 1.0]],
+        };
+
+        let bytes = calibration.to_bytes();
+        assert_eq!(CalibrationData::from_bytes(&bytes).unwrap(), calibration);
+    }
+
+    #[test]
+    fn calibration_rejects_corruption() {
+        let bytes = CalibrationData::default().to_bytes();
+
+        for i in 4..CalibrationData::SIZE {
+            let mut corrupted = bytes;
+            corrupted[i] ^= 0x01;
+            assert!(matches!(
+                CalibrationData::from_bytes(&corrupted),
+                Err(SensorError::BadCalibrationChecksum)
+            ));
+        }
+    }
+
+    #[test]
+    fn calibration_rejects_bad_format() {
+        let bytes = CalibrationData::default().to_bytes();
+
+        let mut bad_magic = bytes;
+        bad_magic[0] = b'X';
+        let mut bad_version = bytes;
+        bad_version[2] = CalibrationData::VERSION + 1;
+
+        for bytes in [
+            &bad_magic[..],
+            &bad_version[..],
+            &bytes[..CalibrationData::SIZE - 1],
+        ] {
+            assert!(matches!(
+                CalibrationData::from_bytes(bytes),
+                Err(SensorError::BadCalibrationFormat)
+            ));
+        }
+    }
+
+    #[test]
+    fn face_detect() {
+        assert_eq!(Face::detect(F32x3::new(0.98, 0.05, -0.1)), Some(Face::XUp));
+        assert_eq!(Face::detect(F32x3::new(-1.0, 0.0, 0.0)), Some(Face::XDown));
+        assert_eq!(Face::detect(F32x3::new(0.0, 1.0, 0.0)), Some(Face::YUp));
+        assert_eq!(Face::detect(F32x3::new(0.1, -0.97, 0.0)), Some(Face::YDown));
+        assert_eq!(Face::detect(F32x3::new(0.0, 0.0, 1.0)), Some(Face::ZUp));
+        assert_eq!(Face::detect(F32x3::new(0.0, 0.0, -1.0)), Some(Face::ZDown));
+
+        // Tilted roughly 45 degrees between two axes
+        assert_eq!(Face::detect(F32x3::new(0.7, 0.0, 0.7)), None);
+
+        // Not close to 1 g
+        assert_eq!(Face::detect(F32x3::new(0.0, 0.0, 0.0)), None);
+        assert_eq!(Face::detect(F32x3::new(0.5, 0.0, 0.0)), None);
+        assert_eq!(Face::detect(F32x3::new(0.0, 0.0, -1.5)), None);
+    }
+
+    #[test]
+    fn six_position_solve() {
+        let bias = [0.02, -0.03, 0.05];
+        let gain = [1.01, 0.98, 1.03];
+
+        let mut calibrator = SixPositionCalibrator::new();
+        assert!(calibrator.solve().is_none());
+
+        for face in Face::ALL {
+            let axis = face as usize / 2;
+            let sign = if face as usize % 2 == 0 { 1.0 } else { -1.0 };
+
+            let mut reading = bias;
+            reading[axis] += sign * gain[axis];
+            let reading = F32x3::new(reading[0], reading[1], reading[2]);
+
+            assert_eq!(calibrator.next_missing(), Some(face));
+            assert_eq!(calibrator.add_reading(reading).unwrap(), face);
+        }
+        assert!(calibrator.is_complete());
+
+        let calibration = calibrator.solve().unwrap();
+        for axis in 0..3 {
+            assert_close(calibration.bias[axis], bias[axis]);
+            assert_close(calibration.scale[axis], 1.0 / gain[axis]);
+        }
+
+        let corrected = calibration.apply(F32x3::new(bias[0], bias[1], bias[2] + gain[2]));
+        assert_close(corrected.x, 0.0);
+        assert_close(corrected.y, 0.0);
+        assert_close(corrected.z, 1.0);
+    }
+
+    #[test]
+    fn six_position_rejects_ambiguous_orientation() {
+        let mut calibrator = SixPositionCalibrator::new();
+
+        assert!(matches!(
+            calibrator.add_reading(F32x3::new(0.6, 0.6, 0.5)),
+            Err(SensorError::AmbiguousOrientation)
+        ));
+    }
+
+    #[test]
+    fn gyro_bias_tracker_clamps_gain() {
+        assert_eq!(GyroBiasTracker::new(10, 2.0).gain, 1.0);
+        assert_eq!(GyroBiasTracker::new(10, 0.5).gain, 0.5);
+        assert_eq!(GyroBiasTracker::new(10, -1.0).gain, f32::EPSILON);
+        assert_eq!(GyroBiasTracker::new(10, f32::NAN).gain, f32::EPSILON);
+    }
+
+    #[test]
+    fn gyro_bias_tracker_follows_stationary_bias() {
+        let mut tracker = GyroBiasTracker::new(10, 0.5);
+        let accel = F32x3::new(0.0, 0.0, 1.0);
+        let gyro = F32x3::new(0.4, -0.2, 0.1);
+
+        for _ in 0..10 * 20 {
+            tracker.update(accel, gyro);
+        }
+
+        assert!(tracker.is_stationary());
+        assert_close(tracker.bias().x, gyro.x);
+        assert_close(tracker.bias().y, gyro.y);
+        assert_close(tracker.bias().z, gyro.z);
+        assert_close(tracker.confidence(), 1.0);
+
+        let corrected = tracker.update(accel, gyro);
+        assert_close(corrected.x, 0.0);
+        assert_close(corrected.y, 0.0);
+        assert_close(corrected.z, 0.0);
+    }
+
+    #[test]
+    fn gyro_bias_tracker_ignores_moving_windows() {
+        let mut tracker = GyroBiasTracker::new(10, 0.5);
+        let accel = F32x3::new(0.0, 0.0, 1.0);
+
+        for i in 0..10 * 20 {
+            // Rotating back and forth at 10 deg/sec
+            let rate = if i % 2 == 0 { 10.0 } else { -10.0 };
+            tracker.update(accel, F32x3::new(rate, 0.0, 0.0));
+        }
+
+        assert!(!tracker.is_stationary());
+        assert_eq!(tracker.bias(), F32x3::default());
+        assert_eq!(tracker.confidence(), 0.0);
+    }
+}
@@ -0,0 +1,409 @@
+use core::fmt::Debug;
+
+use accelerometer::vector::F32x3;
+use embedded_hal::{delay::DelayNs, i2c::I2c};
+
+use crate::{
+    calibration::abs,
+    error::SensorError,
+    Error,
+    Icm42670,
+    ACCEL_STATIONARY_VARIANCE,
+    GYRO_STATIONARY_VARIANCE,
+};
+
+/// Temperature around which the compensation polynomials are expanded, in
+/// degrees centigrade
+const REFERENCE_TEMPERATURE: f32 = 25.0;
+
+/// Minimum difference between two temperatures for them to be treated as
+/// distinct when fitting, in degrees centigrade
+const MIN_TEMPERATURE_SEPARATION: f32 = 0.5;
+
+/// Smallest pivot accepted when solving the normal equations, relative to the
+/// largest entry of the matrix
+const RELATIVE_PIVOT_TOLERANCE: f32 = 1e-6;
+
+impl<I2C, E> Icm42670<I2C>
+where
+    I2C: I2c<Error = E>,
+    E: Debug,
+{
+    /// Measure the gyroscope and accelerometer bias at the current temperature
+    /// by averaging `samples` readings while the device is stationary, and
+    /// add the result to the recorder
+    ///
+    /// `gravity` is the accelerometer reading, in g, which is expected in the
+    /// orientation the device is resting in (e.g. `(0, 0, 1)` when lying flat
+    /// with +Z pointing up). Biases are measured relative to the calibrated
+    /// output, but without any temperature compensation applied.
+    pub fn record_temp_bias(
+        &mut self,
+        delay: &mut impl DelayNs,
+        samples: u16,
+        gravity: F32x3,
+        recorder: &mut TempBiasRecorder,
+    ) -> Result<TempBiasPoint, Error<E>> {
+        if samples < 2 {
+            return Err(Error::SensorError(SensorError::NotEnoughSamples));
+        }
+
+        // Measure the temperature either side of the samples, as it changes far
+        // more slowly than the sampling period.
+        let start_temperature = self.temperature()?;
+        let (accel_stats, gyro_stats) = self.sample_stats(delay, samples)?;
+        let temperature = (start_temperature + self.temperature()?) / 2.0;
+
+        if accel_stats.max_variance() > ACCEL_STATIONARY_VARIANCE
+            || gyro_stats.max_variance() > GYRO_STATIONARY_VARIANCE
+        {
+            return Err(Error::SensorError(SensorError::NotStationary));
+        }
+
+        // The calibration is an affine correction, so may equally be applied to
+        // the mean of the samples.
+        let accel = self.calibration.correct_accel(accel_stats.mean());
+        let point = TempBiasPoint {
+            temperature,
+            gyro_bias: self.calibration.correct_gyro(gyro_stats.mean()),
+            accel_bias: F32x3::new(
+                accel.x - gravity.x,
+                accel.y - gravity.y,
+                accel.z - gravity.z,
+            ),
+        };
+        recorder.add(point)?;
+
+        Ok(point)
+    }
+
+    /// Return the temperature compensation currently applied to the normalized
+    /// gyro and accelerometer data, if any
+    pub fn temp_compensation(&self) -> Option<&TempCompensation> {
+        self.temp_compensation.as_ref()
+    }
+
+    /// Set the temperature compensation to apply to the normalized gyro and
+    /// accelerometer data
+    ///
+    /// While compensation is enabled, every call to `gyro_norm` or
+    /// `accel_norm` additionally reads the temperature sensor.
+    pub fn set_temp_compensation(&mut self, compensation: Option<TempCompensation>) {
+        self.temp_compensation = compensation;
+    }
+}
+
+/// Gyroscope and accelerometer bias measured at a single temperature
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TempBiasPoint {
+    /// Temperature at which the bias was measured, in degrees centigrade
+    pub temperature: f32,
+    /// Gyroscope bias, in deg/sec
+    pub gyro_bias: F32x3,
+    /// Accelerometer bias, in g
+    pub accel_bias: F32x3,
+}
+
+/// Fixed-capacity collection of biases measured at different temperatures,
+/// from which a [TempCompensation] model can be fitted
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TempBiasRecorder {
+    points: [TempBiasPoint; TempBiasRecorder::CAPACITY],
+    len: usize,
+}
+
+impl TempBiasRecorder {
+    /// Maximum number of points which can be recorded
+    pub const CAPACITY: usize = 16;
+
+    /// Create a new, empty recorder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a measured point to the recorder
+    pub fn add(&mut self, point: TempBiasPoint) -> Result<(), SensorError> {
+        if self.len == Self::CAPACITY {
+            return Err(SensorError::TooManySamples);
+        }
+
+        self.points[self.len] = point;
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// All points recorded so far
+    pub fn points(&self) -> &[TempBiasPoint] {
+        &self.points[..self.len]
+    }
+
+    /// Remove all recorded points
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Fit a per-axis polynomial of the given degree (at most 2) to the
+    /// recorded biases using least squares
+    ///
+    /// At least `degree + 1` points at distinct temperatures are required.
+    pub fn fit(&self, degree: usize) -> Result<TempCompensation, SensorError> {
+        if degree > TempCompensation::MAX_DEGREE {
+            return Err(SensorError::UnsupportedFitDegree);
+        }
+
+        let terms = degree + 1;
+        if self.len < terms {
+            return Err(SensorError::NotEnoughSamples);
+        }
+        if self.distinct_temperatures() < terms {
+            return Err(SensorError::DegenerateFit);
+        }
+
+        // Build the normal equations, which are shared between all six signals;
+        // unused terms are left as identity rows so that they solve to zero.
+        let mut normal = [[0.0; 3]; 3];
+        for (i, row) in normal.iter_mut().enumerate().skip(terms) {
+            row[i] = 1.0;
+        }
+
+        let mut rhs = [[0.0; 3]; 6];
+        for point in self.points() {
+            let powers = powers(point.temperature);
+            let signals = [
+                point.gyro_bias.x,
+                point.gyro_bias.y,
+                point.gyro_bias.z,
+                point.accel_bias.x,
+                point.accel_bias.y,
+                point.accel_bias.z,
+            ];
+
+            for i in 0..terms {
+                for j in 0..terms {
+                    normal[i][j] += powers[i] * powers[j];
+                }
+                for (acc, signal) in rhs.iter_mut().zip(signals) {
+                    acc[i] += powers[i] * signal;
+                }
+            }
+        }
+
+        let mut coeffs = [[0.0; 3]; 6];
+        for (coeffs, rhs) in coeffs.iter_mut().zip(rhs) {
+            *coeffs = solve3(normal, rhs).ok_or(SensorError::DegenerateFit)?;
+        }
+
+        Ok(TempCompensation {
+            gyro: [coeffs[0], coeffs[1], coeffs[2]],
+            accel: [coeffs[3], coeffs[4], coeffs[5]],
+        })
+    }
+
+    /// Number of recorded points whose temperatures differ from those of all
+    /// earlier points
+    fn distinct_temperatures(&self) -> usize {
+        let points = self.points();
+
+        points
+            .iter()
+            .enumerate()
+            .filter(|(i, point)| {
+                points[..*i].iter().all(|other| {
+                    abs(other.temperature - point.temperature) >= MIN_TEMPERATURE_SEPARATION
+                })
+            })
+            .count()
+    }
+}
+
+/// Per-axis polynomial model of the gyroscope and accelerometer bias as a
+/// function of temperature
+///
+/// Each axis has coefficients `[c0, c1, c2]` such that the bias at temperature
+/// `t` is `c0 + c1 * dt + c2 * dt²`, where `dt = t - 25 °C`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TempCompensation {
+    /// Gyroscope bias coefficients for the X, Y and Z axes, in deg/sec
+    pub gyro: [[f32; 3]; 3],
+    /// Accelerometer bias coefficients for the X, Y and Z axes, in g
+    pub accel: [[f32; 3]; 3],
+}
+
+impl TempCompensation {
+    /// Highest polynomial degree supported by the model
+    pub const MAX_DEGREE: usize = 2;
+
+    /// Modelled gyroscope bias at the given temperature, in deg/sec
+    pub fn gyro_bias(&self, temperature: f32) -> F32x3 {
+        evaluate(&self.gyro, temperature)
+    }
+
+    /// Modelled accelerometer bias at the given temperature, in g
+    pub fn accel_bias(&self, temperature: f32) -> F32x3 {
+        evaluate(&self.accel, temperature)
+    }
+}
+
+/// Powers of the temperature offset from the reference temperature
+fn powers(temperature: f32) -> [f32; 3] {
+    let dt = temperature - REFERENCE_TEMPERATURE;
+
+    [1.0, dt, dt * dt]
+}
+
+/// Evaluate the per-axis polynomials at the given temperature
+fn evaluate(coeffs: &[[f32; 3]; 3], temperature: f32) -> F32x3 {
+    let p = powers(temperature);
+    let eval = |c: &[f32; 3]| c[0] * p[0] + c[1] * p[1] + c[2] * p[2];
+
+    F32x3::new(eval(&coeffs[0]), eval(&coeffs[1]), eval(&coeffs[2]))
+}
+
+/// Solve the 3x3 linear system `a * x = b` using Gaussian elimination with
+/// partial pivoting, returning `None` if the system is singular
+fn solve3(mut a: [[f32; 3]; 3], mut b: [f32; 3]) -> Option<[f32; 3]> {
+    let scale = a
+        .iter()
+        .flatten()
+        .fold(0.0, |max: f32, value| max.max(abs(*value)));
+    let tolerance = scale * RELATIVE_PIVOT_TOLERANCE;
+
+    for col in 0..3 {
+        let pivot = (col..3).max_by(|&i, &j| {
+            abs(a[i][col])
+                .partial_cmp(&abs(a[j][col]))
+                .unwrap_or(core::cmp::Ordering::Equal)
+        })?;
+        if abs(a[pivot][col]) <= tolerance {
+            return None;
+        }
+
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+
+        let pivot_row = a[col];
+        for row in col + 1..3 {
+            let factor = a[row][col] / pivot_row[col];
+            for (value, pivot) in a[row].iter_mut().zip(pivot_row).skip(col) {
+                *value -= factor * pivot;
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+
+    let mut x = [0.0; 3];
+    for row in (0..3).rev() {
+        let sum: f32 = (row + 1..3).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+
+    Some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(temperature: f32, gyro_x: f32) -> TempBiasPoint {
+        TempBiasPoint {
+            temperature,
+            gyro_bias: F32x3::new(gyro_x, 0.0, 0.0),
+            accel_bias: F32x3::new(0.0, 0.0, 0.0),
+        }
+    }
+
+    fn recorder(points: &[TempBiasPoint]) -> TempBiasRecorder {
+        let mut recorder = TempBiasRecorder::new();
+        for point in points {
+            recorder.add(*point).unwrap();
+        }
+
+        recorder
+    }
+
+    #[test]
+    fn fit_linear() {
+        let recorder = recorder(&[point(15.0, -0.5), point(25.0, 0.0), point(45.0, 1.0)]);
+        let model = recorder.fit(1).unwrap();
+
+        assert!(abs(model.gyro_bias(25.0).x) < 1e-4);
+        assert!(abs(model.gyro_bias(35.0).x - 0.5) < 1e-4);
+        assert!(abs(model.gyro[0][2]) < 1e-6);
+    }
+
+    #[test]
+    fn fit_quadratic() {
+        let bias = |t: f32| 0.2 + 0.01 * (t - 25.0) - 0.002 * (t - 25.0) * (t - 25.0);
+        let recorder = recorder(&[
+            point(-20.0, bias(-20.0)),
+            point(5.0, bias(5.0)),
+            point(25.0, bias(25.0)),
+            point(60.0, bias(60.0)),
+        ]);
+        let model = recorder.fit(2).unwrap();
+
+        for t in [-10.0, 30.0, 50.0] {
+            assert!(abs(model.gyro_bias(t).x - bias(t)) < 1e-3);
+        }
+    }
+
+    #[test]
+    fn fit_rejects_repeated_temperatures() {
+        let recorder = recorder(&[point(-18.37, 1.0), point(-18.37, 1.1), point(66.41, 2.0)]);
+
+        assert!(matches!(recorder.fit(2), Err(SensorError::DegenerateFit)));
+        assert!(recorder.fit(1).is_ok());
+    }
+
+    #[test]
+    fn fit_rejects_unsupported_degree() {
+        let recorder = recorder(&[
+            point(0.0, 0.0),
+            point(10.0, 0.0),
+            point(20.0, 0.0),
+            point(30.0, 0.0),
+        ]);
+
+        assert!(matches!(
+            recorder.fit(3),
+            Err(SensorError::UnsupportedFitDegree)
+        ));
+    }
+
+    #[test]
+    fn fit_requires_enough_points() {
+        let recorder = recorder(&[point(0.0, 0.0), point(10.0, 0.0)]);
+
+        assert!(matches!(
+            recorder.fit(2),
+            Err(SensorError::NotEnoughSamples)
+        ));
+    }
+
+    #[test]
+    fn solve3_rejects_underdetermined_quadratic() {
+        let mut normal = [[0.0; 3]; 3];
+        for t in [-18.37, -18.37, 66.41] {
+            let p = powers(t);
+            for i in 0..3 {
+                for j in 0..3 {
+                    normal[i][j] += p[i] * p[j];
+                }
+            }
+        }
+
+        assert!(solve3(normal, [1.0, 2.0, 3.0]).is_none());
+    }
+
+    #[test]
+    fn solve3_singular() {
+        let a = [
+            [1.0e6, 2.0e6, 3.0e6],
+            [2.0e6, 4.0e6, 6.0e6],
+            [1.0, 0.0, 0.0],
+        ];
+
+        assert!(solve3(a, [1.0, 2.0, 3.0]).is_none());
+    }
+}
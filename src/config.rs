@@ -148,17 +148,40 @@ impl TryFrom<u8> for GyroRange {
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum PowerMode {
     /// Gyroscope: OFF, Accelerometer: OFF
-    Sleep           = 0b0000,
+    Sleep                     = 0b0000,
     /// Gyroscope: DRIVE ON, Accelerometer: OFF
-    Standby         = 0b0100,
+    Standby                   = 0b0100,
     /// Gyroscope: OFF, Accelerometer: DUTY-CYCLED
-    AccelLowPower   = 0b0010,
+    AccelLowPower             = 0b0010,
     /// Gyroscope: OFF, Accelerometer: ON
-    AccelLowNoise   = 0b0011,
+    AccelLowNoise             = 0b0011,
     /// Gyroscope: ON, Accelerometer: OFF
-    GyroLowNoise    = 0b1100,
+    GyroLowNoise              = 0b1100,
     /// Gyroscope: ON, Accelerometer: ON
-    SixAxisLowNoise = 0b1111,
+    SixAxisLowNoise           = 0b1111,
+    /// Gyroscope: DRIVE ON, Accelerometer: DUTY-CYCLED
+    GyroStandbyAccelLowPower  = 0b0110,
+    /// Gyroscope: DRIVE ON, Accelerometer: ON
+    GyroStandbyAccelLowNoise  = 0b0111,
+    /// Gyroscope: ON, Accelerometer: DUTY-CYCLED
+    GyroLowNoiseAccelLowPower = 0b1110,
+}
+
+impl PowerMode {
+    /// Is the accelerometer duty-cycled in this mode?
+    pub(crate) fn accel_low_power(self) -> bool {
+        self as u8 & 0b11 == 0b10
+    }
+
+    /// Is the accelerometer running continuously in this mode?
+    pub(crate) fn accel_low_noise(self) -> bool {
+        self as u8 & 0b11 == 0b11
+    }
+
+    /// Is the gyroscope producing output in this mode?
+    pub(crate) fn gyro_low_noise(self) -> bool {
+        self as u8 >> 2 == 0b11
+    }
 }
 
 impl Bitfield for PowerMode {
@@ -185,24 +208,55 @@ impl TryFrom<u8> for PowerMode {
     fn try_from(value: u8) -> Result<Self, Self::Error> {
         use PowerMode::*;
 
-        match value {
-            0b0000 => Ok(Sleep),
-            0b0100 => Ok(Standby),
-            0b0010 => Ok(AccelLowPower),
-            0b0011 => Ok(AccelLowNoise),
-            0b1100 => Ok(GyroLowNoise),
-            0b1111 => Ok(SixAxisLowNoise),
+        // `GYRO_MODE` and `ACCEL_MODE` are decoded separately, as any
+        // combination of the two may be read back from the device. An
+        // `ACCEL_MODE` of 0b01 turns the accelerometer off, like 0b00 does.
+        let gyro = (value >> 2) & 0b11;
+        let accel = match value & 0b11 {
+            0b01 => 0b00,
+            accel => accel,
+        };
+
+        match (gyro, accel) {
+            (0b00, 0b00) => Ok(Sleep),
+            (0b01, 0b00) => Ok(Standby),
+            (0b00, 0b10) => Ok(AccelLowPower),
+            (0b00, 0b11) => Ok(AccelLowNoise),
+            (0b11, 0b00) => Ok(GyroLowNoise),
+            (0b11, 0b11) => Ok(SixAxisLowNoise),
+            (0b01, 0b10) => Ok(GyroStandbyAccelLowPower),
+            (0b01, 0b11) => Ok(GyroStandbyAccelLowNoise),
+            (0b11, 0b10) => Ok(GyroLowNoiseAccelLowPower),
+            // A `GYRO_MODE` of 0b10 is reserved
             _ => Err(SensorError::InvalidDiscriminant),
         }
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Idle {
+    Disabled = 0b0,
+    Enabled  = 0b1,
+}
+
+impl Bitfield for Idle {
+    const BITMASK: u8 = 0b0001_0000;
+    type Reg = Bank0;
+    const REGISTER: Self::Reg = Self::Reg::PWR_MGMT0;
+
+    fn bits(self) -> u8 {
+        // `IDLE` occupies bit 4 in the register; while set the internal RC
+        // oscillator is kept running even if both sensors are off
+        (self as u8) << 4
+    }
+}
+
 /// Accelerometer ODR selection values
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum AccelOdr {
     /// 1.6 kHz (LN mode)
     Hz1600   = 0b0101,
-    /// 800 Hz (LN mode
+    /// 800 Hz (LN mode)
     Hz800    = 0b0110,
     /// 400 Hz (LP or LN mode)
     Hz400    = 0b0111,
@@ -220,7 +274,7 @@ pub enum AccelOdr {
     Hz6_25   = 0b1101,
     /// 3.125 Hz (LP mode)
     Hz3_125  = 0b1110,
-    /// 1.5625 Hz (LP mode
+    /// 1.5625 Hz (LP mode)
     Hz1_5625 = 0b1111,
 }
 
@@ -242,6 +296,21 @@ impl AccelOdr {
             Hz1_5625 => 1.5625,
         }
     }
+
+    /// Can the accelerometer run at this output data rate in the given power
+    /// mode?
+    pub fn is_supported_in(self, mode: PowerMode) -> bool {
+        use AccelOdr::*;
+
+        if mode.accel_low_power() {
+            !matches!(self, Hz1600 | Hz800)
+        } else if mode.accel_low_noise() {
+            !matches!(self, Hz6_25 | Hz3_125 | Hz1_5625)
+        } else {
+            // The accelerometer is off, so any rate may be configured.
+            true
+        }
+    }
 }
 
 impl Bitfield for AccelOdr {
@@ -305,6 +374,31 @@ impl Bitfield for AccLpAvg {
     }
 }
 
+impl Default for AccLpAvg {
+    fn default() -> Self {
+        Self::X32
+    }
+}
+
+impl TryFrom<u8> for AccLpAvg {
+    type Error = SensorError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        use AccLpAvg::*;
+
+        match value {
+            0b000 => Ok(X2),
+            0b001 => Ok(X4),
+            0b010 => Ok(X8),
+            0b011 => Ok(X16),
+            0b100 => Ok(X32),
+            // `0b110` and `0b111` also select 64x averaging
+            0b101..=0b111 => Ok(X64),
+            _ => Err(SensorError::InvalidDiscriminant),
+        }
+    }
+}
+
 /// Acceleration Digital Low Pass Filter options
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum AccelDlpfBw {
@@ -328,6 +422,32 @@ impl Bitfield for AccelDlpfBw {
     }
 }
 
+impl Default for AccelDlpfBw {
+    fn default() -> Self {
+        Self::Hz180
+    }
+}
+
+impl TryFrom<u8> for AccelDlpfBw {
+    type Error = SensorError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        use AccelDlpfBw::*;
+
+        match value {
+            0b000 => Ok(Bypassed),
+            0b001 => Ok(Hz180),
+            0b010 => Ok(Hz121),
+            0b011 => Ok(Hz73),
+            0b100 => Ok(Hz53),
+            0b101 => Ok(Hz34),
+            0b110 => Ok(Hz25),
+            0b111 => Ok(Hz16),
+            _ => Err(SensorError::InvalidDiscriminant),
+        }
+    }
+}
+
 /// Temperature DLPF (Digital Low Pass Filter) Bandwidth
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum TempDlpfBw {
@@ -349,6 +469,32 @@ impl Bitfield for TempDlpfBw {
     }
 }
 
+impl Default for TempDlpfBw {
+    fn default() -> Self {
+        Self::Bypassed
+    }
+}
+
+impl TryFrom<u8> for TempDlpfBw {
+    type Error = SensorError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        use TempDlpfBw::*;
+
+        match value {
+            0b000 => Ok(Bypassed),
+            0b001 => Ok(Hz180),
+            0b010 => Ok(Hz72),
+            0b011 => Ok(Hz34),
+            0b100 => Ok(Hz16),
+            0b101 => Ok(Hz8),
+            // `0b111` also selects the 4 Hz bandwidth
+            0b110 | 0b111 => Ok(Hz4),
+            _ => Err(SensorError::InvalidDiscriminant),
+        }
+    }
+}
+
 /// Gyroscope UI low pass filter bandwidth
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum GyroLpFiltBw {
@@ -370,6 +516,33 @@ impl Bitfield for GyroLpFiltBw {
         self as u8
     }
 }
+
+impl Default for GyroLpFiltBw {
+    fn default() -> Self {
+        Self::Hz180
+    }
+}
+
+impl TryFrom<u8> for GyroLpFiltBw {
+    type Error = SensorError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        use GyroLpFiltBw::*;
+
+        match value {
+            0b000 => Ok(Bypassed),
+            0b001 => Ok(Hz180),
+            0b010 => Ok(Hz121),
+            0b011 => Ok(Hz73),
+            0b100 => Ok(Hz53),
+            0b101 => Ok(Hz34),
+            0b110 => Ok(Hz25),
+            0b111 => Ok(Hz16),
+            _ => Err(SensorError::InvalidDiscriminant),
+        }
+    }
+}
+
 /// Gyroscope ODR selection values
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum GyroOdr {
@@ -445,10 +618,738 @@ impl TryFrom<u8> for GyroOdr {
     }
 }
 
+/// Number of over-threshold events required before the Wake-on-Motion
+/// interrupt is asserted
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WomIntDur {
+    /// Assert on the 1st over-threshold event
+    Events1 = 0b00,
+    /// Assert on the 2nd over-threshold event
+    Events2 = 0b01,
+    /// Assert on the 3rd over-threshold event
+    Events3 = 0b10,
+    /// Assert on the 4th over-threshold event
+    Events4 = 0b11,
+}
+
+impl Bitfield for WomIntDur {
+    const BITMASK: u8 = 0b0001_1000;
+    type Reg = Bank0;
+    const REGISTER: Self::Reg = Self::Reg::WOM_CONFIG;
+
+    fn bits(self) -> u8 {
+        // `WOM_INT_DUR` occupies bits 4:3 in the register
+        (self as u8) << 3
+    }
+}
+
+impl Default for WomIntDur {
+    fn default() -> Self {
+        Self::Events1
+    }
+}
+
+impl TryFrom<u8> for WomIntDur {
+    type Error = SensorError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        use WomIntDur::*;
+
+        match value {
+            0b00 => Ok(Events1),
+            0b01 => Ok(Events2),
+            0b10 => Ok(Events3),
+            0b11 => Ok(Events4),
+            _ => Err(SensorError::InvalidDiscriminant),
+        }
+    }
+}
+
+/// How the per-axis Wake-on-Motion events are combined into an interrupt
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WomIntMode {
+    /// Assert when any enabled axis exceeds its threshold
+    Or  = 0b0,
+    /// Assert when all enabled axes exceed their thresholds
+    And = 0b1,
+}
+
+impl Bitfield for WomIntMode {
+    const BITMASK: u8 = 0b0000_0100;
+    type Reg = Bank0;
+    const REGISTER: Self::Reg = Self::Reg::WOM_CONFIG;
+
+    fn bits(self) -> u8 {
+        // `WOM_INT_MODE` occupies bit 2 in the register
+        (self as u8) << 2
+    }
+}
+
+impl Default for WomIntMode {
+    fn default() -> Self {
+        Self::Or
+    }
+}
+
+impl TryFrom<u8> for WomIntMode {
+    type Error = SensorError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        use WomIntMode::*;
+
+        match value {
+            0b0 => Ok(Or),
+            0b1 => Ok(And),
+            _ => Err(SensorError::InvalidDiscriminant),
+        }
+    }
+}
+
+/// Sample against which accelerometer samples are compared for Wake-on-Motion
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WomMode {
+    /// Compare to the first sample taken after Wake-on-Motion was enabled
+    Initial  = 0b0,
+    /// Compare to the previous sample
+    Previous = 0b1,
+}
+
+impl Bitfield for WomMode {
+    const BITMASK: u8 = 0b0000_0010;
+    type Reg = Bank0;
+    const REGISTER: Self::Reg = Self::Reg::WOM_CONFIG;
+
+    fn bits(self) -> u8 {
+        // `WOM_MODE` occupies bit 1 in the register
+        (self as u8) << 1
+    }
+}
+
+impl Default for WomMode {
+    fn default() -> Self {
+        Self::Initial
+    }
+}
+
+impl TryFrom<u8> for WomMode {
+    type Error = SensorError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        use WomMode::*;
+
+        match value {
+            0b0 => Ok(Initial),
+            0b1 => Ok(Previous),
+            _ => Err(SensorError::InvalidDiscriminant),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum WomEnable {
+    Disabled = 0b0,
+    Enabled  = 0b1,
+}
+
+impl Bitfield for WomEnable {
+    const BITMASK: u8 = 0b0000_0001;
+    type Reg = Bank0;
+    const REGISTER: Self::Reg = Self::Reg::WOM_CONFIG;
+
+    fn bits(self) -> u8 {
+        // `WOM_EN` occupies bit 0 in the register
+        self as u8
+    }
+}
+
+/// Interrupt pins of the device
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InterruptPin {
+    /// Pin `INT1`
+    Int1,
+    /// Pin `INT2`
+    Int2,
+}
+
+/// Interrupt pin signalling mode
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IntMode {
+    /// The pin is pulsed when an interrupt occurs
+    Pulsed  = 0b0,
+    /// The pin is held until the interrupt status is read
+    Latched = 0b1,
+}
+
+impl Default for IntMode {
+    fn default() -> Self {
+        Self::Pulsed
+    }
+}
+
+/// Interrupt pin drive circuit
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IntDrive {
+    /// Open drain output
+    OpenDrain = 0b0,
+    /// Push-pull output
+    PushPull  = 0b1,
+}
+
+impl Default for IntDrive {
+    fn default() -> Self {
+        Self::OpenDrain
+    }
+}
+
+/// Interrupt pin polarity
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IntPolarity {
+    /// The pin is driven low when asserted
+    ActiveLow  = 0b0,
+    /// The pin is driven high when asserted
+    ActiveHigh = 0b1,
+}
+
+impl Default for IntPolarity {
+    fn default() -> Self {
+        Self::ActiveLow
+    }
+}
+
+/// Output data rate at which the APEX motion features run on the DMP
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DmpOdr {
+    /// 25 Hz
+    Hz25  = 0b00,
+    /// 400 Hz
+    Hz400 = 0b01,
+    /// 50 Hz
+    Hz50  = 0b10,
+    /// 100 Hz
+    Hz100 = 0b11,
+}
+
+impl DmpOdr {
+    pub fn as_f32(self) -> f32 {
+        use DmpOdr::*;
+
+        match self {
+            Hz25 => 25.0,
+            Hz400 => 400.0,
+            Hz50 => 50.0,
+            Hz100 => 100.0,
+        }
+    }
+
+    /// Accelerometer output data rate which must be configured for the DMP to
+    /// run at this rate
+    pub fn accel_odr(self) -> AccelOdr {
+        use DmpOdr::*;
+
+        match self {
+            Hz25 => AccelOdr::Hz25,
+            Hz400 => AccelOdr::Hz400,
+            Hz50 => AccelOdr::Hz50,
+            Hz100 => AccelOdr::Hz100,
+        }
+    }
+}
+
+impl Bitfield for DmpOdr {
+    const BITMASK: u8 = 0b0000_0011;
+    type Reg = Bank0;
+    const REGISTER: Self::Reg = Self::Reg::APEX_CONFIG1;
+
+    fn bits(self) -> u8 {
+        // `DMP_ODR` occupies bits 1:0 in the register
+        self as u8
+    }
+}
+
+impl Default for DmpOdr {
+    fn default() -> Self {
+        Self::Hz50
+    }
+}
+
+impl TryFrom<u8> for DmpOdr {
+    type Error = SensorError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        use DmpOdr::*;
+
+        match value {
+            0b00 => Ok(Hz25),
+            0b01 => Ok(Hz400),
+            0b10 => Ok(Hz50),
+            0b11 => Ok(Hz100),
+            _ => Err(SensorError::InvalidDiscriminant),
+        }
+    }
+}
+
+/// DMP power save mode, in which the DMP is only woken by Wake-on-Motion
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DmpPowerSave {
+    /// The DMP runs continuously
+    Disabled = 0b0,
+    /// The DMP sleeps until woken by Wake-on-Motion
+    Enabled  = 0b1,
+}
+
+impl Bitfield for DmpPowerSave {
+    const BITMASK: u8 = 0b0000_1000;
+    type Reg = Bank0;
+    const REGISTER: Self::Reg = Self::Reg::APEX_CONFIG0;
+
+    fn bits(self) -> u8 {
+        // `DMP_POWER_SAVE_EN` occupies bit 3 in the register
+        (self as u8) << 3
+    }
+}
+
+impl Default for DmpPowerSave {
+    fn default() -> Self {
+        Self::Enabled
+    }
+}
+
+/// Activity classification reported by the pedometer
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Activity {
+    /// No activity, or activity which could not be classified
+    Unknown = 0b00,
+    /// Walking
+    Walk    = 0b01,
+    /// Running
+    Run     = 0b10,
+}
+
+impl TryFrom<u8> for Activity {
+    type Error = SensorError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        use Activity::*;
+
+        match value {
+            0b00 => Ok(Unknown),
+            0b01 => Ok(Walk),
+            0b10 => Ok(Run),
+            _ => Err(SensorError::InvalidDiscriminant),
+        }
+    }
+}
+
+/// Pedometer high energy threshold, used to reject false steps from
+/// high-energy motions such as shaking
+///
+/// Thresholds are expressed in units of 1/1024 g, as in `PED_HI_EN_TH_SEL`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PedHighEnergyTh {
+    /// 90/1024 g (≈87.9 mg)
+    Th90  = 0b00,
+    /// 107/1024 g (≈104.5 mg)
+    Th107 = 0b01,
+    /// 136/1024 g (≈132.8 mg)
+    Th136 = 0b10,
+    /// 159/1024 g (≈155.3 mg)
+    Th159 = 0b11,
+}
+
+impl Default for PedHighEnergyTh {
+    fn default() -> Self {
+        Self::Th107
+    }
+}
+
+impl TryFrom<u8> for PedHighEnergyTh {
+    type Error = SensorError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        use PedHighEnergyTh::*;
+
+        match value {
+            0b00 => Ok(Th90),
+            0b01 => Ok(Th107),
+            0b10 => Ok(Th136),
+            0b11 => Ok(Th159),
+            _ => Err(SensorError::InvalidDiscriminant),
+        }
+    }
+}
+
+/// Pedometer sensitivity mode
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PedSensitivity {
+    /// Normal mode, suitable for most use cases
+    Normal   = 0b0,
+    /// Slow walk mode, which detects slower steps at the cost of more false
+    /// positives
+    SlowWalk = 0b1,
+}
+
+impl Default for PedSensitivity {
+    fn default() -> Self {
+        Self::Normal
+    }
+}
+
+impl TryFrom<u8> for PedSensitivity {
+    type Error = SensorError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        use PedSensitivity::*;
+
+        match value {
+            0b0 => Ok(Normal),
+            0b1 => Ok(SlowWalk),
+            _ => Err(SensorError::InvalidDiscriminant),
+        }
+    }
+}
+
+/// Time for which the device must remain tilted before a tilt event is
+/// reported
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TiltWaitTime {
+    /// 0 seconds
+    S0 = 0b00,
+    /// 2 seconds
+    S2 = 0b01,
+    /// 4 seconds
+    S4 = 0b10,
+    /// 6 seconds
+    S6 = 0b11,
+}
+
+impl Default for TiltWaitTime {
+    fn default() -> Self {
+        Self::S4
+    }
+}
+
+impl TryFrom<u8> for TiltWaitTime {
+    type Error = SensorError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        use TiltWaitTime::*;
+
+        match value {
+            0b00 => Ok(S0),
+            0b01 => Ok(S2),
+            0b10 => Ok(S4),
+            0b11 => Ok(S6),
+            _ => Err(SensorError::InvalidDiscriminant),
+        }
+    }
+}
+
+/// Minimum distance which must be fallen for a freefall to be reported, as
+/// selected by `FF_MIN_DURATION_SEL`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FreefallMinDistance {
+    /// 10 cm
+    Cm10 = 0,
+    /// 12 cm
+    Cm12 = 1,
+    /// 13 cm
+    Cm13 = 2,
+    /// 16 cm
+    Cm16 = 3,
+    /// 18 cm
+    Cm18 = 4,
+    /// 20 cm
+    Cm20 = 5,
+    /// 23 cm
+    Cm23 = 6,
+    /// 25 cm
+    Cm25 = 7,
+    /// 28 cm
+    Cm28 = 8,
+    /// 31 cm
+    Cm31 = 9,
+    /// 34 cm
+    Cm34 = 10,
+    /// 38 cm
+    Cm38 = 11,
+    /// 41 cm
+    Cm41 = 12,
+    /// 45 cm
+    Cm45 = 13,
+    /// 48 cm
+    Cm48 = 14,
+    /// 52 cm
+    Cm52 = 15,
+}
+
+impl Default for FreefallMinDistance {
+    fn default() -> Self {
+        Self::Cm12
+    }
+}
+
+impl TryFrom<u8> for FreefallMinDistance {
+    type Error = SensorError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        use FreefallMinDistance::*;
+
+        match value {
+            0 => Ok(Cm10),
+            1 => Ok(Cm12),
+            2 => Ok(Cm13),
+            3 => Ok(Cm16),
+            4 => Ok(Cm18),
+            5 => Ok(Cm20),
+            6 => Ok(Cm23),
+            7 => Ok(Cm25),
+            8 => Ok(Cm28),
+            9 => Ok(Cm31),
+            10 => Ok(Cm34),
+            11 => Ok(Cm38),
+            12 => Ok(Cm41),
+            13 => Ok(Cm45),
+            14 => Ok(Cm48),
+            15 => Ok(Cm52),
+            _ => Err(SensorError::InvalidDiscriminant),
+        }
+    }
+}
+
+/// Maximum distance which may be fallen for a freefall to be reported, as
+/// selected by `FF_MAX_DURATION_SEL`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FreefallMaxDistance {
+    /// 102 cm
+    Cm102 = 0,
+    /// 120 cm
+    Cm120 = 1,
+    /// 139 cm
+    Cm139 = 2,
+    /// 159 cm
+    Cm159 = 3,
+    /// 181 cm
+    Cm181 = 4,
+    /// 204 cm
+    Cm204 = 5,
+    /// 228 cm
+    Cm228 = 6,
+    /// 254 cm
+    Cm254 = 7,
+    /// 281 cm
+    Cm281 = 8,
+    /// 310 cm
+    Cm310 = 9,
+    /// 339 cm
+    Cm339 = 10,
+    /// 371 cm
+    Cm371 = 11,
+    /// 403 cm
+    Cm403 = 12,
+    /// 438 cm
+    Cm438 = 13,
+    /// 473 cm
+    Cm473 = 14,
+    /// 510 cm
+    Cm510 = 15,
+}
+
+impl Default for FreefallMaxDistance {
+    fn default() -> Self {
+        Self::Cm204
+    }
+}
+
+impl TryFrom<u8> for FreefallMaxDistance {
+    type Error = SensorError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        use FreefallMaxDistance::*;
+
+        match value {
+            0 => Ok(Cm102),
+            1 => Ok(Cm120),
+            2 => Ok(Cm139),
+            3 => Ok(Cm159),
+            4 => Ok(Cm181),
+            5 => Ok(Cm204),
+            6 => Ok(Cm228),
+            7 => Ok(Cm254),
+            8 => Ok(Cm281),
+            9 => Ok(Cm310),
+            10 => Ok(Cm339),
+            11 => Ok(Cm371),
+            12 => Ok(Cm403),
+            13 => Ok(Cm438),
+            14 => Ok(Cm473),
+            15 => Ok(Cm510),
+            _ => Err(SensorError::InvalidDiscriminant),
+        }
+    }
+}
+
+/// APEX motion feature interrupts which can be routed to an interrupt pin
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ApexInterrupt {
+    /// A step was detected
+    StepDetect        = 5,
+    /// The pedometer step count overflowed
+    StepCountOverflow = 4,
+    /// A tilt was detected
+    Tilt              = 3,
+    /// A freefall was detected
+    Freefall          = 2,
+    /// A low-g event was detected
+    LowG              = 1,
+    /// A high-g event was detected
+    HighG             = 0,
+}
+
+/// Significant motion detection sensitivity, as selected by
+/// `SMD_SENSITIVITY_SEL`
+///
+/// Higher levels make the algorithm more robust at rejecting motion which is
+/// not significant, at the cost of detecting significant motion less readily.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SmdSensitivity {
+    /// Most sensitive
+    Level0 = 0b000,
+    /// Sensitivity level 1
+    Level1 = 0b001,
+    /// Sensitivity level 2
+    Level2 = 0b010,
+    /// Sensitivity level 3
+    Level3 = 0b011,
+    /// Most robust to false detections
+    Level4 = 0b100,
+}
+
+impl Default for SmdSensitivity {
+    fn default() -> Self {
+        Self::Level0
+    }
+}
+
+impl TryFrom<u8> for SmdSensitivity {
+    type Error = SensorError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        use SmdSensitivity::*;
+
+        match value {
+            0b000 => Ok(Level0),
+            0b001 => Ok(Level1),
+            0b010 => Ok(Level2),
+            0b011 => Ok(Level3),
+            0b100 => Ok(Level4),
+            _ => Err(SensorError::InvalidDiscriminant),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum SmdEnable {
+    Disabled = 0b0,
+    Enabled  = 0b1,
+}
+
+impl Bitfield for SmdEnable {
+    const BITMASK: u8 = 0b0100_0000;
+    type Reg = Bank0;
+    const REGISTER: Self::Reg = Self::Reg::APEX_CONFIG1;
+
+    fn bits(self) -> u8 {
+        // `SMD_ENABLE` occupies bit 6 in the register
+        (self as u8) << 6
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum FreefallEnable {
+    Disabled = 0b0,
+    Enabled  = 0b1,
+}
+
+impl Bitfield for FreefallEnable {
+    const BITMASK: u8 = 0b0010_0000;
+    type Reg = Bank0;
+    const REGISTER: Self::Reg = Self::Reg::APEX_CONFIG1;
+
+    fn bits(self) -> u8 {
+        // `FF_ENABLE` occupies bit 5 in the register
+        (self as u8) << 5
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum TiltEnable {
+    Disabled = 0b0,
+    Enabled  = 0b1,
+}
+
+impl Bitfield for TiltEnable {
+    const BITMASK: u8 = 0b0001_0000;
+    type Reg = Bank0;
+    const REGISTER: Self::Reg = Self::Reg::APEX_CONFIG1;
+
+    fn bits(self) -> u8 {
+        // `TILT_ENABLE` occupies bit 4 in the register
+        (self as u8) << 4
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum PedEnable {
+    Disabled = 0b0,
+    Enabled  = 0b1,
+}
+
+impl Bitfield for PedEnable {
+    const BITMASK: u8 = 0b0000_1000;
+    type Reg = Bank0;
+    const REGISTER: Self::Reg = Self::Reg::APEX_CONFIG1;
+
+    fn bits(self) -> u8 {
+        // `PED_ENABLE` occupies bit 3 in the register
+        (self as u8) << 3
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum DmpInit {
+    Enabled = 0b1,
+}
+
+impl Bitfield for DmpInit {
+    const BITMASK: u8 = 0b0000_0100;
+    type Reg = Bank0;
+    const REGISTER: Self::Reg = Self::Reg::APEX_CONFIG0;
+
+    fn bits(self) -> u8 {
+        // `DMP_INIT_EN` occupies bit 2 in the register
+        (self as u8) << 2
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum DmpMemReset {
+    Enabled = 0b1,
+}
+
+impl Bitfield for DmpMemReset {
+    const BITMASK: u8 = 0b0000_0001;
+    type Reg = Bank0;
+    const REGISTER: Self::Reg = Self::Reg::APEX_CONFIG0;
+
+    fn bits(self) -> u8 {
+        // `DMP_MEM_RESET_EN` occupies bit 0 in the register
+        self as u8
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub(crate) enum SoftReset {
-    Enabled   = 0b0,
-    _Disabled = 0b1,
+    Enabled = 0b1,
 }
 
 impl Bitfield for SoftReset {
@@ -457,6 +1358,8 @@ impl Bitfield for SoftReset {
     const REGISTER: Self::Reg = Self::Reg::SIGNAL_PATH_RESET;
 
     fn bits(self) -> u8 {
+        // `SOFT_RESET_DEVICE_CONFIG` occupies bit 4 in the register, and is
+        // cleared by the device once the reset has been triggered
         (self as u8) << 4
     }
 }
@@ -678,3 +1581,36 @@ impl Bitfield for SpiSlewRate {
         self as u8
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn power_mode_decodes_each_field() {
+        for mode in [
+            PowerMode::Sleep,
+            PowerMode::Standby,
+            PowerMode::AccelLowPower,
+            PowerMode::AccelLowNoise,
+            PowerMode::GyroLowNoise,
+            PowerMode::SixAxisLowNoise,
+            PowerMode::GyroStandbyAccelLowPower,
+            PowerMode::GyroStandbyAccelLowNoise,
+            PowerMode::GyroLowNoiseAccelLowPower,
+        ] {
+            assert_eq!(PowerMode::try_from(mode.bits()).unwrap(), mode);
+        }
+
+        // An `ACCEL_MODE` of 0b01 also turns the accelerometer off
+        assert_eq!(PowerMode::try_from(0b0001).unwrap(), PowerMode::Sleep);
+        assert_eq!(PowerMode::try_from(0b0101).unwrap(), PowerMode::Standby);
+        assert_eq!(
+            PowerMode::try_from(0b1101).unwrap(),
+            PowerMode::GyroLowNoise
+        );
+
+        // A `GYRO_MODE` of 0b10 is reserved
+        assert!(PowerMode::try_from(0b1000).is_err());
+    }
+}
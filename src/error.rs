@@ -25,6 +25,52 @@ pub enum SensorError {
     /// Attempted to create an AccelRange or GyroRange enum from an invalid
     /// discriminant
     InvalidDiscriminant,
+    /// Attempted to program a user offset which lies outside of the range
+    /// supported by the offset registers
+    OffsetOutOfRange,
+    /// Too few samples were requested for a calibration routine to produce a
+    /// meaningful result
+    NotEnoughSamples,
+    /// The device was moving while a calibration routine required it to be
+    /// stationary
+    NotStationary,
+    /// The device was not resting close enough to one of the six
+    /// gravity-aligned orientations used during accelerometer calibration
+    AmbiguousOrientation,
+    /// A calibration blob has the wrong size, magic number or version
+    BadCalibrationFormat,
+    /// A calibration blob failed its integrity check
+    BadCalibrationChecksum,
+    /// More samples were provided than a calibration routine is able to store
+    TooManySamples,
+    /// A calibration fit of a higher polynomial degree than supported was
+    /// requested
+    UnsupportedFitDegree,
+    /// A calibration fit could not be solved, most likely because the samples
+    /// were not sufficiently distinct
+    DegenerateFit,
+    /// Attempted to configure a detection threshold outside of the range
+    /// supported by the device
+    ThresholdOutOfRange,
+    /// The accelerometer is not running at an output data rate supported by
+    /// the APEX motion features
+    UnsupportedApexOdr,
+    /// The accelerometer output data rate is not supported in the requested
+    /// power mode
+    UnsupportedOdrForPowerMode,
+    /// Attempted to change a setting which cannot be changed while the
+    /// accelerometer is in low-power mode
+    NotAllowedInLowPower,
+    /// The internal clock did not start, most likely because the device is in
+    /// a power mode which does not support accessing the MREG registers
+    ClockNotRunning,
+    /// The device did not report completion of the on-chip self-test within
+    /// the timeout
+    SelfTestTimeout,
+    /// The device did not report completion of a soft reset within the timeout
+    ResetTimeout,
+    /// The gyroscope is not running in low-noise mode, so produces no output
+    GyroNotRunning,
 }
 
 impl<E> From<SensorError> for Error<E> {
@@ -0,0 +1,93 @@
+use core::fmt::Debug;
+
+use accelerometer::vector::{F32x3, VectorExt};
+use embedded_hal::{delay::DelayNs, i2c::I2c};
+
+use crate::{register::Bank0, Error, Icm42670, PowerMode, GYRO_STATIONARY_VARIANCE};
+
+/// Number of gyroscope samples used to estimate the noise during a health
+/// check
+const HEALTH_CHECK_SAMPLES: u16 = 50;
+
+/// Accepted range of the measured gravity magnitude, in g
+const GRAVITY_MIN: f32 = 0.8;
+const GRAVITY_MAX: f32 = 1.2;
+
+/// Outcome of each of the checks performed by [Icm42670::health_check]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HealthReport {
+    /// Value read from `WHO_AM_I`
+    pub device_id: u8,
+    /// Does the device ID match one of [Icm42670::DEVICE_IDS]?
+    pub device_id_ok: bool,
+    /// Did the internal clock report as running within the timeout?
+    pub clock_ready: bool,
+    /// Power mode reported by the device, or `None` if it is not one of the
+    /// known modes
+    pub power_mode: Option<PowerMode>,
+    /// Does the reported power mode match the one most recently set through
+    /// the driver?
+    pub power_mode_ok: bool,
+    /// Mean accelerometer reading, in g
+    pub accel: F32x3,
+    /// Magnitude of the mean accelerometer reading, in g
+    pub gravity: f32,
+    /// Is the gravity magnitude plausible for a device at rest?
+    pub gravity_ok: bool,
+    /// Variance of the gyroscope output of each axis, in (deg/sec)²
+    pub gyro_variance: F32x3,
+    /// Is the gyroscope noise below [GYRO_STATIONARY_VARIANCE] on all axes?
+    pub gyro_noise_ok: bool,
+}
+
+impl HealthReport {
+    /// Did every check pass?
+    pub fn is_healthy(&self) -> bool {
+        self.device_id_ok
+            && self.clock_ready
+            && self.power_mode_ok
+            && self.gravity_ok
+            && self.gyro_noise_ok
+    }
+}
+
+impl<I2C, E> Icm42670<I2C>
+where
+    I2C: I2c<Error = E>,
+    E: Debug,
+{
+    /// Perform a series of sanity checks on the device, intended to be run at
+    /// boot while the device is at rest
+    ///
+    /// Rather than stopping at the first failure, every check is performed and
+    /// its outcome recorded in the returned report. An error is only returned
+    /// if communication with the device fails.
+    pub fn health_check(&mut self, delay: &mut impl DelayNs) -> Result<HealthReport, Error<E>> {
+        let device_id = self.device_id()?;
+        let device_id_ok = Self::DEVICE_IDS.contains(&device_id);
+
+        let clock_ready = self.clock_ready(delay)?;
+
+        // `GYRO_MODE` occupies bits 3:2 and `ACCEL_MODE` bits 1:0 in the register
+        let power_mode = PowerMode::try_from(self.read_reg(&Bank0::PWR_MGMT0)? & 0xF).ok();
+        let power_mode_ok = power_mode == Some(self.power_mode);
+
+        let (accel_stats, gyro_stats) = self.sample_stats(delay, HEALTH_CHECK_SAMPLES)?;
+
+        let accel = accel_stats.mean();
+        let gravity = accel.magnitude();
+
+        Ok(HealthReport {
+            device_id,
+            device_id_ok,
+            clock_ready,
+            power_mode,
+            power_mode_ok,
+            accel,
+            gravity,
+            gravity_ok: (GRAVITY_MIN..=GRAVITY_MAX).contains(&gravity),
+            gyro_variance: gyro_stats.variance(),
+            gyro_noise_ok: gyro_stats.max_variance() <= GYRO_STATIONARY_VARIANCE,
+        })
+    }
+}
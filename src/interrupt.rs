@@ -0,0 +1,280 @@
+use core::fmt::Debug;
+
+use embedded_hal::{delay::DelayNs, i2c::I2c};
+
+use crate::{
+    register::{Bank0, Mreg1, RegisterBank},
+    wom::WomStatus,
+    ApexInterrupt,
+    Error,
+    Icm42670,
+    IntDrive,
+    IntMode,
+    IntPolarity,
+    InterruptPin,
+};
+
+/// Electrical configuration of an interrupt pin
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct InterruptPinConfig {
+    /// Pulsed or latched signalling
+    pub mode: IntMode,
+    /// Open drain or push-pull output
+    pub drive: IntDrive,
+    /// Active low or active high output
+    pub polarity: IntPolarity,
+}
+
+impl InterruptPinConfig {
+    fn bits(&self) -> u8 {
+        (self.mode as u8) << 2 | (self.drive as u8) << 1 | self.polarity as u8
+    }
+
+    fn from_bits(bits: u8) -> Self {
+        Self {
+            mode: if bits & 0b100 != 0 {
+                IntMode::Latched
+            } else {
+                IntMode::Pulsed
+            },
+            drive: if bits & 0b010 != 0 {
+                IntDrive::PushPull
+            } else {
+                IntDrive::OpenDrain
+            },
+            polarity: if bits & 0b001 != 0 {
+                IntPolarity::ActiveHigh
+            } else {
+                IntPolarity::ActiveLow
+            },
+        }
+    }
+}
+
+/// Raw interrupt routing of a single interrupt pin
+///
+/// Each field holds the enable bits of the corresponding `INT_SOURCEn`
+/// register, as described in the datasheet. The individual features also
+/// provide dedicated methods for routing their interrupts, such as
+/// [Icm42670::set_wom_interrupt] and [Icm42670::set_apex_interrupt].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct InterruptSources {
+    /// Data, FIFO and status interrupts (`INT_SOURCE0` or `INT_SOURCE3`)
+    pub data: u8,
+    /// Wake-on-Motion and significant motion interrupts (`INT_SOURCE1` or
+    /// `INT_SOURCE4`)
+    pub motion: u8,
+    /// APEX motion feature interrupts (`INT_SOURCE6` or `INT_SOURCE7`)
+    pub apex: u8,
+}
+
+/// Decoded interrupt status of the motion features
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct InterruptStatus {
+    /// Axes on which Wake-on-Motion was detected
+    pub wom: WomStatus,
+    /// Significant motion was detected
+    pub smd: bool,
+    /// A step was detected by the pedometer
+    pub step_detected: bool,
+    /// The pedometer step count overflowed
+    pub step_count_overflow: bool,
+    /// A tilt was detected
+    pub tilt: bool,
+    /// A freefall was detected
+    pub freefall: bool,
+    /// A low-g event was detected
+    pub low_g: bool,
+    /// A high-g event was detected
+    pub high_g: bool,
+}
+
+impl InterruptStatus {
+    fn from_bits(status2: u8, status3: u8) -> Self {
+        // `STEP_DET_INT`, `STEP_CNT_OVF_INT`, `TILT_DET_INT`, `FF_DET_INT`,
+        // `LOWG_DET_INT` and `HIGHG_DET_INT` occupy bits 5:0
+        Self {
+            wom: WomStatus::from_bits(status2),
+            // `SMD_INT` occupies bit 3
+            smd: status2 & 0b0000_1000 != 0,
+            step_detected: status3 & 0b0010_0000 != 0,
+            step_count_overflow: status3 & 0b0001_0000 != 0,
+            tilt: status3 & 0b0000_1000 != 0,
+            freefall: status3 & 0b0000_0100 != 0,
+            low_g: status3 & 0b0000_0010 != 0,
+            high_g: status3 & 0b0000_0001 != 0,
+        }
+    }
+}
+
+impl InterruptPin {
+    /// Bit offset of this pin's fields within `INT_CONFIG`
+    fn config_shift(self) -> u8 {
+        match self {
+            InterruptPin::Int1 => 0,
+            InterruptPin::Int2 => 3,
+        }
+    }
+
+    /// Interrupt source register which routes the `INT_STATUS` interrupts to
+    /// this pin
+    fn source0(self) -> Bank0 {
+        match self {
+            InterruptPin::Int1 => Bank0::INT_SOURCE0,
+            InterruptPin::Int2 => Bank0::INT_SOURCE3,
+        }
+    }
+
+    /// Interrupt source register which routes the `INT_STATUS2` interrupts to
+    /// this pin
+    pub(crate) fn source1(self) -> Bank0 {
+        match self {
+            InterruptPin::Int1 => Bank0::INT_SOURCE1,
+            InterruptPin::Int2 => Bank0::INT_SOURCE4,
+        }
+    }
+
+    /// Interrupt source register which routes the APEX interrupts to this pin
+    fn apex_source(self) -> Mreg1 {
+        match self {
+            InterruptPin::Int1 => Mreg1::INT_SOURCE6,
+            InterruptPin::Int2 => Mreg1::INT_SOURCE7,
+        }
+    }
+}
+
+impl<I2C, E> Icm42670<I2C>
+where
+    I2C: I2c<Error = E>,
+    E: Debug,
+{
+    /// Return the electrical configuration of the given interrupt pin
+    pub fn interrupt_pin_config(
+        &mut self,
+        pin: InterruptPin,
+    ) -> Result<InterruptPinConfig, Error<E>> {
+        // `INT1_*` occupies bits 2:0 and `INT2_*` bits 5:3 in the register
+        let bits = self.read_reg(&Bank0::INT_CONFIG)? >> pin.config_shift();
+
+        Ok(InterruptPinConfig::from_bits(bits))
+    }
+
+    /// Set the electrical configuration of the given interrupt pin
+    pub fn set_interrupt_pin_config(
+        &mut self,
+        pin: InterruptPin,
+        config: InterruptPinConfig,
+    ) -> Result<(), Error<E>> {
+        let shift = pin.config_shift();
+
+        self.modify_reg(&Bank0::INT_CONFIG, 0b111 << shift, config.bits() << shift)
+    }
+
+    /// Return the raw interrupt routing of the given pin
+    pub fn interrupt_sources(
+        &mut self,
+        delay: &mut impl DelayNs,
+        pin: InterruptPin,
+    ) -> Result<InterruptSources, Error<E>> {
+        Ok(InterruptSources {
+            data: self.read_reg(&pin.source0())?,
+            motion: self.read_reg(&pin.source1())?,
+            apex: self.read_mreg(delay, RegisterBank::MReg1, &pin.apex_source())?,
+        })
+    }
+
+    /// Set the raw interrupt routing of the given pin, replacing any routing
+    /// which was previously configured
+    pub fn set_interrupt_sources(
+        &mut self,
+        delay: &mut impl DelayNs,
+        pin: InterruptPin,
+        sources: &InterruptSources,
+    ) -> Result<(), Error<E>> {
+        self.write_reg(&pin.source0(), sources.data)?;
+        self.write_reg(&pin.source1(), sources.motion)?;
+        self.write_mreg(delay, RegisterBank::MReg1, &pin.apex_source(), sources.apex)
+    }
+
+    /// Enable or disable routing of an APEX motion feature interrupt to the
+    /// given pin
+    pub fn set_apex_interrupt(
+        &mut self,
+        delay: &mut impl DelayNs,
+        pin: InterruptPin,
+        interrupt: ApexInterrupt,
+        enabled: bool,
+    ) -> Result<(), Error<E>> {
+        // The discriminant of each interrupt is its bit position in the register
+        let mask = 1 << interrupt as u8;
+        let bits = if enabled { mask } else { 0 };
+
+        self.modify_mreg(delay, RegisterBank::MReg1, &pin.apex_source(), mask, bits)
+    }
+
+    /// Read and clear the interrupt status of the motion features
+    pub fn interrupt_status(&mut self) -> Result<InterruptStatus, Error<E>> {
+        let status2 = self.read_reg(&Bank0::INT_STATUS2)?;
+        let status3 = self.read_reg(&Bank0::INT_STATUS3)?;
+
+        Ok(InterruptStatus::from_bits(status2, status3))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apex_routing_matches_status() {
+        let cases = [
+            (
+                ApexInterrupt::StepDetect,
+                InterruptStatus {
+                    step_detected: true,
+                    ..Default::default()
+                },
+            ),
+            (
+                ApexInterrupt::StepCountOverflow,
+                InterruptStatus {
+                    step_count_overflow: true,
+                    ..Default::default()
+                },
+            ),
+            (
+                ApexInterrupt::Tilt,
+                InterruptStatus {
+                    tilt: true,
+                    ..Default::default()
+                },
+            ),
+            (
+                ApexInterrupt::Freefall,
+                InterruptStatus {
+                    freefall: true,
+                    ..Default::default()
+                },
+            ),
+            (
+                ApexInterrupt::LowG,
+                InterruptStatus {
+                    low_g: true,
+                    ..Default::default()
+                },
+            ),
+            (
+                ApexInterrupt::HighG,
+                InterruptStatus {
+                    high_g: true,
+                    ..Default::default()
+                },
+            ),
+        ];
+
+        for (interrupt, expected) in cases {
+            let mask = 1 << interrupt as u8;
+            assert_eq!(InterruptStatus::from_bits(0, mask), expected);
+        }
+    }
+}
@@ -25,29 +25,89 @@ use accelerometer::{
 use embedded_hal::{delay::DelayNs, i2c::I2c};
 
 pub use crate::{
+    apex::{
+        ApexFeatures,
+        FreefallConfig,
+        FreefallDebounce,
+        HighGConfig,
+        LowGConfig,
+        PedometerConfig,
+    },
+    builder::{Config, ConfigBuilder},
+    calibration::{
+        AccelCalibration,
+        CalibrationData,
+        Face,
+        GyroBiasTracker,
+        SixPositionCalibrator,
+        ACCEL_STATIONARY_VARIANCE,
+        GYRO_STATIONARY_VARIANCE,
+    },
+    compensation::{TempBiasPoint, TempBiasRecorder, TempCompensation},
     config::{
         AccLpAvg,
         AccelDlpfBw,
         AccelOdr,
         AccelRange,
+        Activity,
         Address,
+        ApexInterrupt,
+        DmpOdr,
+        DmpPowerSave,
+        FreefallMaxDistance,
+        FreefallMinDistance,
         GyroLpFiltBw,
         GyroOdr,
         GyroRange,
+        IntDrive,
+        IntMode,
+        IntPolarity,
+        InterruptPin,
+        PedHighEnergyTh,
+        PedSensitivity,
         PowerMode,
+        SmdSensitivity,
         TempDlpfBw,
+        TiltWaitTime,
+        WomIntDur,
+        WomIntMode,
+        WomMode,
     },
-    error::Error,
+    error::{Error, SensorError},
+    health::HealthReport,
+    interrupt::{InterruptPinConfig, InterruptSources, InterruptStatus},
+    self_test::SelfTestResult,
+    wom::{WomConfig, WomProfileGuard, WomStatus},
 };
 use crate::{
     config::{Bitfield, SoftReset},
-    error::SensorError,
-    register::{Bank0, Register, RegisterBank},
+    register::{Bank0, Mreg1, Register, RegisterBank},
 };
 
+mod apex;
+mod builder;
+mod calibration;
+mod compensation;
 mod config;
 mod error;
+mod health;
+mod interrupt;
 mod register;
+mod self_test;
+mod wom;
+
+/// Number of times `MCLK_RDY` is polled before the clock is reported as not
+/// running
+const MCLK_POLL_ATTEMPTS: u16 = 100;
+
+/// Number of times `RESET_DONE_INT` is polled, 100µs apart, before a soft
+/// reset is considered to have timed out
+const RESET_POLL_ATTEMPTS: u16 = 10;
+
+/// Resolution of the gyroscope user offset registers, in LSB per deg/sec
+const GYRO_OFFSET_SCALE: f32 = 32.0;
+/// Resolution of the accelerometer user offset registers, in LSB per g
+const ACCEL_OFFSET_SCALE: f32 = 2048.0;
 
 /// Re-export any traits which may be required by end users
 pub mod prelude {
@@ -64,6 +124,13 @@ pub struct Icm42670<I2C> {
     i2c: I2C,
     /// I²C slave address to use
     address: Address,
+    /// Calibration applied to the normalized sensor data
+    calibration: CalibrationData,
+    /// Temperature-dependent bias compensation applied to the normalized
+    /// sensor data, if enabled
+    temp_compensation: Option<TempCompensation>,
+    /// Power mode most recently set through the driver
+    power_mode: PowerMode,
 }
 
 impl<I2C, E> Icm42670<I2C>
@@ -82,7 +149,13 @@ where
 
     /// Instantiate a new instance of the driver and initialize the device
     pub fn new(i2c: I2C, address: Address) -> Result<Self, Error<E>> {
-        let mut me = Self { i2c, address };
+        let mut me = Self {
+            i2c,
+            address,
+            calibration: CalibrationData::default(),
+            temp_compensation: None,
+            power_mode: PowerMode::default(),
+        };
 
         // Verify that the device has the correct ID before continuing. If the ID does
         // not match either of the expected values then it is likely the wrong chip is
@@ -96,6 +169,10 @@ where
         me.set_accel_range(AccelRange::default())?;
         me.set_gyro_range(GyroRange::default())?;
 
+        // The output data rate must be one which is supported in low-noise mode
+        // before the sensors are enabled below.
+        me.set_accel_odr(AccelOdr::default())?;
+
         // The IMU uses `PowerMode::Sleep` by default, which disables both the accel and
         // gyro, so we enable them both during driver initialization.
         me.set_power_mode(PowerMode::SixAxisLowNoise)?;
@@ -103,6 +180,30 @@ where
         Ok(me)
     }
 
+    /// Instantiate a new instance of the driver without reinitializing the
+    /// device
+    ///
+    /// Only the device ID is verified, and the current power mode is read back
+    /// into the driver's state. No registers are written, so any configuration
+    /// previously applied (e.g. by a bootloader) is left untouched.
+    pub fn attach(i2c: I2C, address: Address) -> Result<Self, Error<E>> {
+        let mut me = Self {
+            i2c,
+            address,
+            calibration: CalibrationData::default(),
+            temp_compensation: None,
+            power_mode: PowerMode::default(),
+        };
+
+        if !Self::DEVICE_IDS.contains(&me.device_id()?) {
+            return Err(Error::SensorError(SensorError::BadChip));
+        }
+
+        me.power_mode = me.power_mode()?;
+
+        Ok(me)
+    }
+
     /// Return the raw interface to the underlying `I2C` instance
     pub fn free(self) -> I2C {
         self.i2c
@@ -114,11 +215,50 @@ where
     }
 
     /// Perform a software-reset on the device
-    pub fn soft_reset(&mut self) -> Result<(), Error<E>> {
-        self.update_reg(SoftReset::Enabled)
+    ///
+    /// This blocks until the reset has completed, after which the device ID is
+    /// verified again. If the device does not report completion of the reset
+    /// within the timeout, [SensorError::ResetTimeout] is returned. All
+    /// registers are returned to their reset values, which leaves both
+    /// sensors off; if `config` is provided it is applied once the
+    /// reset has completed. The calibration and temperature compensation held
+    /// by the driver are left untouched.
+    pub fn soft_reset(
+        &mut self,
+        delay: &mut impl DelayNs,
+        config: Option<&Config>,
+    ) -> Result<(), Error<E>> {
+        self.update_reg(SoftReset::Enabled)?;
+        self.power_mode = PowerMode::default();
+
+        // The device is unavailable for 1ms following a reset, after which
+        // `RESET_DONE_INT` (bit 4 of `INT_STATUS`) is set.
+        delay.delay_ms(1);
+        let mut done = false;
+        for _ in 0..RESET_POLL_ATTEMPTS {
+            if self.read_reg(&Bank0::INT_STATUS)? & 0b0001_0000 != 0 {
+                done = true;
+                break;
+            }
+            delay.delay_us(100);
+        }
+
+        if !done {
+            return Err(Error::SensorError(SensorError::ResetTimeout));
+        }
+
+        if !Self::DEVICE_IDS.contains(&self.device_id()?) {
+            return Err(Error::SensorError(SensorError::BadChip));
+        }
+
+        match config {
+            Some(config) => self.apply_config(delay, config),
+            None => Ok(()),
+        }
     }
 
-    /// Return the normalized gyro data for each of the three axes
+    /// Return the normalized gyro data for each of the three axes, with the
+    /// configured calibration and temperature compensation applied
     pub fn gyro_norm(&mut self) -> Result<F32x3, Error<E>> {
         let range = self.gyro_range()?;
         let scale = range.scale_factor();
@@ -130,7 +270,19 @@ where
         let y = raw.y as f32 / scale;
         let z = raw.z as f32 / scale;
 
-        Ok(F32x3::new(x, y, z))
+        let gyro = self.calibration.correct_gyro(F32x3::new(x, y, z));
+
+        match self.temp_compensation {
+            Some(compensation) => {
+                let bias = compensation.gyro_bias(self.temperature()?);
+                Ok(F32x3::new(
+                    gyro.x - bias.x,
+                    gyro.y - bias.y,
+                    gyro.z - bias.z,
+                ))
+            }
+            None => Ok(gyro),
+        }
     }
 
     /// Read the raw gyro data for each of the three axes
@@ -156,6 +308,12 @@ where
         self.read_reg_i16(&Bank0::TEMP_DATA1, &Bank0::TEMP_DATA0)
     }
 
+    /// Return the currently configured bandwidth of the temperature signal DLPF
+    /// (Digital Low Pass Filter)
+    pub fn temp_dlpf(&mut self) -> Result<TempDlpfBw, Error<E>> {
+        self.read_field()
+    }
+
     /// Sets the bandwidth of the temperature signal DLPF (Digital Low Pass
     /// Filter)
     ///
@@ -167,26 +325,27 @@ where
 
     /// Return the currently configured power mode
     pub fn power_mode(&mut self) -> Result<PowerMode, Error<E>> {
-        //  `GYRO_MODE` occupies bits 3:2 in the register
-        // `ACCEL_MODE` occupies bits 1:0 in the register
-        let bits = self.read_reg(&Bank0::PWR_MGMT0)? & 0xF;
-        let mode = PowerMode::try_from(bits)?;
-
-        Ok(mode)
+        self.read_field()
     }
 
     /// Set the power mode of the IMU
+    ///
+    /// Returns [SensorError::UnsupportedOdrForPowerMode] if the configured
+    /// accelerometer output data rate cannot be used in the requested mode.
     pub fn set_power_mode(&mut self, mode: PowerMode) -> Result<(), Error<E>> {
-        self.update_reg(mode)
+        if !self.accel_odr()?.is_supported_in(mode) {
+            return Err(Error::SensorError(SensorError::UnsupportedOdrForPowerMode));
+        }
+
+        self.update_reg(mode)?;
+        self.power_mode = mode;
+
+        Ok(())
     }
 
     /// Return the currently configured accelerometer range
     pub fn accel_range(&mut self) -> Result<AccelRange, Error<E>> {
-        // `ACCEL_UI_FS_SEL` occupies bits 6:5 in the register
-        let fs_sel = self.read_reg(&Bank0::ACCEL_CONFIG0)? >> 5;
-        let range = AccelRange::try_from(fs_sel)?;
-
-        Ok(range)
+        self.read_field()
     }
 
     /// Set the range of the accelerometer
@@ -194,21 +353,27 @@ where
         self.update_reg(range)
     }
 
+    /// Return the currently configured acceleration low-power averaging value
+    pub fn accel_low_power_avg(&mut self) -> Result<AccLpAvg, Error<E>> {
+        self.read_field()
+    }
+
     /// Set acceleration low-power averaging value.
     ///
     /// This field cannot be changed when the accel sensor is in LPM
-    /// (LowPowerMode)
+    /// (LowPowerMode), in which case [SensorError::NotAllowedInLowPower] is
+    /// returned.
     pub fn set_accel_low_power_avg(&mut self, avg_val: AccLpAvg) -> Result<(), Error<E>> {
+        if self.power_mode.accel_low_power() {
+            return Err(Error::SensorError(SensorError::NotAllowedInLowPower));
+        }
+
         self.update_reg(avg_val)
     }
 
     /// Return the currently configured gyroscope range
     pub fn gyro_range(&mut self) -> Result<GyroRange, Error<E>> {
-        // `GYRO_UI_FS_SEL` occupies bits 6:5 in the register
-        let fs_sel = self.read_reg(&Bank0::GYRO_CONFIG0)? >> 5;
-        let range = GyroRange::try_from(fs_sel)?;
-
-        Ok(range)
+        self.read_field()
     }
 
     /// Set the range of the gyro
@@ -216,6 +381,11 @@ where
         self.update_reg(range)
     }
 
+    /// Return the currently configured GYRO UI low pass filter bandwidth
+    pub fn gyro_lp_filter_bandwidth(&mut self) -> Result<GyroLpFiltBw, Error<E>> {
+        self.read_field()
+    }
+
     /// Selects GYRO UI low pass filter bandwidth
     /// This field can be changed on the fly even if gyro sonsor is on
     pub fn set_gyro_lp_filter_bandwidth(&mut self, freq: GyroLpFiltBw) -> Result<(), Error<E>> {
@@ -224,18 +394,26 @@ where
 
     /// Return the currently configured output data rate for the accelerometer
     pub fn accel_odr(&mut self) -> Result<AccelOdr, Error<E>> {
-        // `ACCEL_ODR` occupies bits 3:0 in the register
-        let odr = self.read_reg(&Bank0::ACCEL_CONFIG0)? & 0xF;
-        let odr = AccelOdr::try_from(odr)?;
-
-        Ok(odr)
+        self.read_field()
     }
 
     /// Set the output data rate of the accelerometer
+    ///
+    /// Returns [SensorError::UnsupportedOdrForPowerMode] if the rate cannot be
+    /// used in the current power mode.
     pub fn set_accel_odr(&mut self, odr: AccelOdr) -> Result<(), Error<E>> {
+        if !odr.is_supported_in(self.power_mode) {
+            return Err(Error::SensorError(SensorError::UnsupportedOdrForPowerMode));
+        }
+
         self.update_reg(odr)
     }
 
+    /// Return the currently configured ACCEL UI low pass filter bandwidth
+    pub fn accel_dlpf_bw(&mut self) -> Result<AccelDlpfBw, Error<E>> {
+        self.read_field()
+    }
+
     /// Selects ACCEL UI low pass filter bandwidth
     /// This field can be changed on-the-fly even if accel sonsor is on
     pub fn set_accel_dlpf_bw(&mut self, dlpf: AccelDlpfBw) -> Result<(), Error<E>> {
@@ -244,11 +422,7 @@ where
 
     /// Return the currently configured output data rate for the gyroscope
     pub fn gyro_odr(&mut self) -> Result<GyroOdr, Error<E>> {
-        // `GYRO_ODR` occupies bits 3:0 in the register
-        let odr = self.read_reg(&Bank0::GYRO_CONFIG0)? & 0xF;
-        let odr = GyroOdr::try_from(odr)?;
-
-        Ok(odr)
+        self.read_field()
     }
 
     /// Set the output data rate of the gyroscope
@@ -256,12 +430,120 @@ where
         self.update_reg(odr)
     }
 
+    /// Return the gyroscope user offsets, in degrees/second
+    pub fn gyro_offset(&mut self, delay: &mut impl DelayNs) -> Result<F32x3, Error<E>> {
+        let user0 = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::OFFSET_USER0)?;
+        let user1 = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::OFFSET_USER1)?;
+        let user2 = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::OFFSET_USER2)?;
+        let user3 = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::OFFSET_USER3)?;
+        let user4 = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::OFFSET_USER4)?;
+
+        // The upper nibbles of the X/Y offsets share `OFFSET_USER1`, and the upper
+        // nibble of the Z offset lives in the low half of `OFFSET_USER4`.
+        let x = unpack_offset(user1 & 0xF, user0);
+        let y = unpack_offset(user1 >> 4, user2);
+        let z = unpack_offset(user4 & 0xF, user3);
+
+        Ok(F32x3::new(
+            x as f32 / GYRO_OFFSET_SCALE,
+            y as f32 / GYRO_OFFSET_SCALE,
+            z as f32 / GYRO_OFFSET_SCALE,
+        ))
+    }
+
+    /// Set the gyroscope user offsets, in degrees/second
+    ///
+    /// The offsets are added to the gyro output by the device itself, so to
+    /// cancel a measured bias the negated bias should be programmed. Each axis
+    /// has a range of ±64 deg/sec with a resolution of 1/32 deg/sec.
+    pub fn set_gyro_offset(
+        &mut self,
+        delay: &mut impl DelayNs,
+        offset: F32x3,
+    ) -> Result<(), Error<E>> {
+        let x = pack_offset(offset.x * GYRO_OFFSET_SCALE)?;
+        let y = pack_offset(offset.y * GYRO_OFFSET_SCALE)?;
+        let z = pack_offset(offset.z * GYRO_OFFSET_SCALE)?;
+
+        // `OFFSET_USER4` is shared with the accelerometer X offset, so we must
+        // preserve its upper nibble.
+        let user4 = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::OFFSET_USER4)?;
+        let user4 = (user4 & 0xF0) | (z >> 8) as u8;
+
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::OFFSET_USER0, x as u8)?;
+        self.write_mreg(
+            delay,
+            RegisterBank::MReg1,
+            &Mreg1::OFFSET_USER1,
+            ((y >> 4) as u8 & 0xF0) | (x >> 8) as u8,
+        )?;
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::OFFSET_USER2, y as u8)?;
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::OFFSET_USER3, z as u8)?;
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::OFFSET_USER4, user4)?;
+
+        Ok(())
+    }
+
+    /// Return the accelerometer user offsets, in g
+    pub fn accel_offset(&mut self, delay: &mut impl DelayNs) -> Result<F32x3, Error<E>> {
+        let user4 = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::OFFSET_USER4)?;
+        let user5 = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::OFFSET_USER5)?;
+        let user6 = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::OFFSET_USER6)?;
+        let user7 = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::OFFSET_USER7)?;
+        let user8 = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::OFFSET_USER8)?;
+
+        // The upper nibble of the X offset lives in the high half of `OFFSET_USER4`,
+        // and the upper nibbles of the Y/Z offsets share `OFFSET_USER7`.
+        let x = unpack_offset(user4 >> 4, user5);
+        let y = unpack_offset(user7 & 0xF, user6);
+        let z = unpack_offset(user7 >> 4, user8);
+
+        Ok(F32x3::new(
+            x as f32 / ACCEL_OFFSET_SCALE,
+            y as f32 / ACCEL_OFFSET_SCALE,
+            z as f32 / ACCEL_OFFSET_SCALE,
+        ))
+    }
+
+    /// Set the accelerometer user offsets, in g
+    ///
+    /// The offsets are added to the accelerometer output by the device itself,
+    /// so to cancel a measured bias the negated bias should be programmed. Each
+    /// axis has a range of ±1 g with a resolution of 0.5 mg.
+    pub fn set_accel_offset(
+        &mut self,
+        delay: &mut impl DelayNs,
+        offset: F32x3,
+    ) -> Result<(), Error<E>> {
+        let x = pack_offset(offset.x * ACCEL_OFFSET_SCALE)?;
+        let y = pack_offset(offset.y * ACCEL_OFFSET_SCALE)?;
+        let z = pack_offset(offset.z * ACCEL_OFFSET_SCALE)?;
+
+        // `OFFSET_USER4` is shared with the gyroscope Z offset, so we must preserve
+        // its lower nibble.
+        let user4 = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::OFFSET_USER4)?;
+        let user4 = (user4 & 0x0F) | ((x >> 4) as u8 & 0xF0);
+
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::OFFSET_USER4, user4)?;
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::OFFSET_USER5, x as u8)?;
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::OFFSET_USER6, y as u8)?;
+        self.write_mreg(
+            delay,
+            RegisterBank::MReg1,
+            &Mreg1::OFFSET_USER7,
+            ((z >> 4) as u8 & 0xF0) | (y >> 8) as u8,
+        )?;
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::OFFSET_USER8, z as u8)?;
+
+        Ok(())
+    }
+
     // -----------------------------------------------------------------------
     // PRIVATE
 
-    // FIXME: 'Sleep mode' and 'accelerometer low power mode with WUOSC' do not
-    //        support MREG1, MREG2 or MREG3 access.
-    #[allow(unused)]
+    // NOTE: 'Sleep mode' and 'accelerometer low power mode with WUOSC' do not
+    //       support MREG1, MREG2 or MREG3 access, in which case
+    //       `SensorError::ClockNotRunning` is returned.
     fn read_mreg(
         &mut self,
         delay: &mut dyn DelayNs,
@@ -271,7 +553,7 @@ where
         // See "ACCESSING MREG1, MREG2 AND MREG3 REGISTERS" (page 40)
 
         // Wait until the internal clock is running prior to writing.
-        while self.read_reg(&Bank0::MCLK_RDY)? != 0x1 {}
+        self.wait_for_mclk(delay)?;
 
         // Select the appropriate block and set the register address to read from.
         self.write_reg(&Bank0::BLK_SEL_R, bank.blk_sel())?;
@@ -289,9 +571,9 @@ where
         Ok(result)
     }
 
-    // FIXME: 'Sleep mode' and 'accelerometer low power mode with WUOSC' do not
-    //        support MREG1, MREG2 or MREG3 access.
-    #[allow(unused)]
+    // NOTE: 'Sleep mode' and 'accelerometer low power mode with WUOSC' do not
+    //       support MREG1, MREG2 or MREG3 access, in which case
+    //       `SensorError::ClockNotRunning` is returned.
     fn write_mreg(
         &mut self,
         delay: &mut dyn DelayNs,
@@ -302,7 +584,7 @@ where
         // See "ACCESSING MREG1, MREG2 AND MREG3 REGISTERS" (page 40)
 
         // Wait until the internal clock is running prior to writing.
-        while self.read_reg(&Bank0::MCLK_RDY)? != 0x1 {}
+        self.wait_for_mclk(delay)?;
 
         // Select the appropriate block and set the register address to write to.
         self.write_reg(&Bank0::BLK_SEL_W, bank.blk_sel())?;
@@ -319,6 +601,83 @@ where
         Ok(())
     }
 
+    /// Read the normalized accelerometer data for each of the three axes, with
+    /// the configured calibration and temperature compensation applied.
+    fn read_accel_norm(&mut self) -> Result<F32x3, Error<E>> {
+        let range = self.accel_range()?;
+        let scale = range.scale_factor();
+
+        // Scale the raw Accelerometer data using the appropriate factor based on the
+        // configured range.
+        let raw = self.read_accel_raw()?;
+        let x = raw.x as f32 / scale;
+        let y = raw.y as f32 / scale;
+        let z = raw.z as f32 / scale;
+
+        let accel = self.calibration.correct_accel(F32x3::new(x, y, z));
+
+        match self.temp_compensation {
+            Some(compensation) => {
+                let bias = compensation.accel_bias(self.temperature()?);
+                Ok(F32x3::new(
+                    accel.x - bias.x,
+                    accel.y - bias.y,
+                    accel.z - bias.z,
+                ))
+            }
+            None => Ok(accel),
+        }
+    }
+
+    /// Read the raw accelerometer data for each of the three axes.
+    fn read_accel_raw(&mut self) -> Result<I16x3, Error<E>> {
+        let x = self.read_reg_i16(&Bank0::ACCEL_DATA_X1, &Bank0::ACCEL_DATA_X0)?;
+        let y = self.read_reg_i16(&Bank0::ACCEL_DATA_Y1, &Bank0::ACCEL_DATA_Y0)?;
+        let z = self.read_reg_i16(&Bank0::ACCEL_DATA_Z1, &Bank0::ACCEL_DATA_Z0)?;
+
+        Ok(I16x3::new(x, y, z))
+    }
+
+    /// Modify only the bits selected by `mask` in the given MREG register,
+    /// preserving all others.
+    fn modify_mreg(
+        &mut self,
+        delay: &mut dyn DelayNs,
+        bank: RegisterBank,
+        reg: &dyn Register,
+        mask: u8,
+        value: u8,
+    ) -> Result<(), Error<E>> {
+        let current = self.read_mreg(delay, bank, reg)?;
+        let value = (current & !mask) | (value & mask);
+
+        self.write_mreg(delay, bank, reg, value)
+    }
+
+    /// Block until the internal clock is reported as running, returning an
+    /// error if it does not start within the timeout.
+    fn wait_for_mclk(&mut self, delay: &mut dyn DelayNs) -> Result<(), Error<E>> {
+        if self.clock_ready(delay)? {
+            Ok(())
+        } else {
+            Err(Error::SensorError(SensorError::ClockNotRunning))
+        }
+    }
+
+    /// Poll until the internal clock is reported as running, returning whether
+    /// it started within the timeout.
+    fn clock_ready(&mut self, delay: &mut dyn DelayNs) -> Result<bool, Error<E>> {
+        for _ in 0..MCLK_POLL_ATTEMPTS {
+            // `MCLK_RDY` occupies bit 3 in the register
+            if self.read_reg(&Bank0::MCLK_RDY)? & 0b0000_1000 != 0 {
+                return Ok(true);
+            }
+            delay.delay_us(10);
+        }
+
+        Ok(false)
+    }
+
     /// Read a register at the provided address.
     fn read_reg<R: Register>(&mut self, reg: &R) -> Result<u8, Error<E>> {
         let mut buffer = [0u8];
@@ -350,19 +709,37 @@ where
         }
     }
 
+    /// Read the value of a bitfield from its register, shifted down and
+    /// decoded using the bitfield's mask.
+    fn read_field<BF>(&mut self) -> Result<BF, Error<E>>
+    where
+        BF: Bitfield + TryFrom<u8, Error = SensorError>,
+    {
+        let bits = self.read_reg(&BF::REGISTER)? & BF::BITMASK;
+        let value = BF::try_from(bits >> BF::BITMASK.trailing_zeros())?;
+
+        Ok(value)
+    }
+
     /// Update the register at the provided address.
     ///
     /// Rather than overwriting any active bits in the register, we first read
     /// in its current value and then update it accordingly using the given
     /// value and mask before writing back the desired value.
     fn update_reg<BF: Bitfield>(&mut self, value: BF) -> Result<(), Error<E>> {
-        if BF::REGISTER.read_only() {
+        self.modify_reg(&BF::REGISTER, BF::BITMASK, value.bits())
+    }
+
+    /// Modify only the bits selected by `mask` in the register at the provided
+    /// address, preserving all others.
+    fn modify_reg<R: Register>(&mut self, reg: &R, mask: u8, value: u8) -> Result<(), Error<E>> {
+        if reg.read_only() {
             Err(Error::SensorError(SensorError::WriteToReadOnly))
         } else {
-            let current = self.read_reg(&BF::REGISTER)?;
-            let value = (current & !BF::BITMASK) | (value.bits() & BF::BITMASK);
+            let current = self.read_reg(reg)?;
+            let value = (current & !mask) | (value & mask);
 
-            self.write_reg(&BF::REGISTER, value)
+            self.write_reg(reg, value)
         }
     }
 }
@@ -375,17 +752,9 @@ where
     type Error = Error<E>;
 
     fn accel_norm(&mut self) -> Result<F32x3, AccelerometerError<Self::Error>> {
-        let range = self.accel_range()?;
-        let scale = range.scale_factor();
+        let norm = self.read_accel_norm()?;
 
-        // Scale the raw Accelerometer data using the appropriate factor based on the
-        // configured range.
-        let raw = self.accel_raw()?;
-        let x = raw.x as f32 / scale;
-        let y = raw.y as f32 / scale;
-        let z = raw.z as f32 / scale;
-
-        Ok(F32x3::new(x, y, z))
+        Ok(norm)
     }
 
     fn sample_rate(&mut self) -> Result<f32, AccelerometerError<Self::Error>> {
@@ -404,10 +773,82 @@ where
     type Error = Error<E>;
 
     fn accel_raw(&mut self) -> Result<I16x3, AccelerometerError<Self::Error>> {
-        let x = self.read_reg_i16(&Bank0::ACCEL_DATA_X1, &Bank0::ACCEL_DATA_X0)?;
-        let y = self.read_reg_i16(&Bank0::ACCEL_DATA_Y1, &Bank0::ACCEL_DATA_Y0)?;
-        let z = self.read_reg_i16(&Bank0::ACCEL_DATA_Z1, &Bank0::ACCEL_DATA_Z0)?;
+        let raw = self.read_accel_raw()?;
 
-        Ok(I16x3::new(x, y, z))
+        Ok(raw)
+    }
+}
+
+/// Combine the upper nibble and lower byte of a 12-bit two's complement user
+/// offset into a sign-extended value.
+fn unpack_offset(hi: u8, lo: u8) -> i16 {
+    let raw = (((hi & 0xF) as u16) << 8 | lo as u16) as i16;
+
+    // Shift the sign bit into bit 15 and back again to sign-extend the value.
+    (raw << 4) >> 4
+}
+
+/// Round a user offset (in LSB) to the nearest integer and convert it to the
+/// 12-bit two's complement representation used by the offset registers.
+fn pack_offset(lsb: f32) -> Result<u16, SensorError> {
+    // Casting would otherwise silently turn NaN into 0.
+    if !lsb.is_finite() {
+        return Err(SensorError::OffsetOutOfRange);
+    }
+
+    let rounded = if lsb >= 0.0 { lsb + 0.5 } else { lsb - 0.5 } as i32;
+
+    if !(-2048..=2047).contains(&rounded) {
+        return Err(SensorError::OffsetOutOfRange);
+    }
+
+    Ok(rounded as u16 & 0x0FFF)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offset_round_trip() {
+        for lsb in [-2048, -1000, -1, 0, 1, 1000, 2047] {
+            let packed = pack_offset(lsb as f32).unwrap();
+            assert_eq!(unpack_offset((packed >> 8) as u8, packed as u8), lsb);
+        }
+    }
+
+    #[test]
+    fn pack_offset_rounds() {
+        assert_eq!(pack_offset(1.4).unwrap(), 1);
+        assert_eq!(pack_offset(1.6).unwrap(), 2);
+        assert_eq!(pack_offset(-1.6).unwrap(), 0x0FFE);
+    }
+
+    #[test]
+    fn pack_offset_range() {
+        assert!(matches!(
+            pack_offset(2047.6),
+            Err(SensorError::OffsetOutOfRange)
+        ));
+        assert!(matches!(
+            pack_offset(-2048.6),
+            Err(SensorError::OffsetOutOfRange)
+        ));
+    }
+
+    #[test]
+    fn pack_offset_rejects_non_finite() {
+        for lsb in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            assert!(matches!(
+                pack_offset(lsb),
+                Err(SensorError::OffsetOutOfRange)
+            ));
+        }
+    }
+
+    #[test]
+    fn unpack_offset_ignores_upper_nibble() {
+        assert_eq!(unpack_offset(0xF8, 0x00), -2048);
+        assert_eq!(unpack_offset(0x07, 0xFF), 2047);
     }
 }
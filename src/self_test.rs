@@ -0,0 +1,404 @@
+use core::fmt::Debug;
+
+use accelerometer::vector::{F32x3, I16x3};
+use embedded_hal::{delay::DelayNs, i2c::I2c};
+
+use crate::{
+    calibration::{abs, sample_period_us},
+    error::SensorError,
+    register::{Bank0, Mreg1, Mreg2, Mreg3, RegisterBank},
+    AccelOdr,
+    AccelRange,
+    Error,
+    GyroOdr,
+    GyroRange,
+    Icm42670,
+    PowerMode,
+};
+
+/// Number of samples averaged with self-test excitation both on and off when
+/// measuring the self-test response
+const SELF_TEST_SAMPLES: u16 = 200;
+
+/// Value written to `ST_CONFIG` before running the on-chip self-test
+///
+/// `ST_NUMBER_SAMPLE` (bit 6) selects 200 samples rather than 16, and the
+/// `ACCEL_ST_LIM` (bits 5:3) and `GYRO_ST_LIM` (bits 2:0) fields select the
+/// widest pass limits, matching the vendor's reference procedure.
+const ST_CONFIG_VALUE: u8 = 0b0111_1111;
+
+/// `SELFTEST` value which triggers the on-chip accelerometer self-test:
+/// `ACCEL_ST_EN` (bit 6) together with `EN_AZ_ST`, `EN_AY_ST` and `EN_AX_ST`
+/// (bits 2:0)
+const SELFTEST_ACCEL: u8 = 0b0100_0111;
+/// `SELFTEST` value which triggers the on-chip gyroscope self-test:
+/// `GYRO_ST_EN` (bit 7) together with `EN_GZ_ST`, `EN_GY_ST` and `EN_GX_ST`
+/// (bits 5:3)
+const SELFTEST_GYRO: u8 = 0b1011_1000;
+/// `SELFTEST` value which applies the excitation to every axis without
+/// triggering the on-chip self-test
+const SELFTEST_EXCITATION: u8 = 0b0011_1111;
+
+/// Number of times `ST_INT` is polled, 10ms apart, before the self-test is
+/// considered to have timed out
+const ST_POLL_ATTEMPTS: u16 = 200;
+
+/// Range used for the accelerometer during self-test, which is the range the
+/// factory trim values were recorded at
+const SELF_TEST_ACCEL_RANGE: AccelRange = AccelRange::G2;
+/// Range used for the gyroscope during self-test, which is the range the
+/// factory trim values were recorded at
+const SELF_TEST_GYRO_RANGE: GyroRange = GyroRange::Deg250;
+
+/// Result of the factory self-test
+///
+/// The verdicts are those reported by the on-chip self-test in `ST_STATUS1` and
+/// `ST_STATUS2`. The measured responses and their ratio to the factory trim
+/// values are provided as additional detail only, and do not contribute to the
+/// verdicts.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SelfTestResult {
+    /// Accelerometer self-test response of each axis, in g
+    pub accel_response: F32x3,
+    /// Gyroscope self-test response of each axis, in deg/sec
+    pub gyro_response: F32x3,
+    /// Approximate ratio of the measured to the factory accelerometer response
+    /// of each axis, or `None` if the device has no factory trim value for
+    /// that axis
+    pub accel_ratio: [Option<f32>; 3],
+    /// Approximate ratio of the measured to the factory gyroscope response of
+    /// each axis, or `None` if the device has no factory trim value for that
+    /// axis
+    pub gyro_ratio: [Option<f32>; 3],
+    /// Pass/fail verdict of each accelerometer axis (`AX_ST_PASS`,
+    /// `AY_ST_PASS` and `AZ_ST_PASS`)
+    pub accel_passed: [bool; 3],
+    /// Pass/fail verdict of each gyroscope axis (`GX_ST_PASS`, `GY_ST_PASS`
+    /// and `GZ_ST_PASS`)
+    pub gyro_passed: [bool; 3],
+    /// Overall accelerometer verdict (`ACCEL_ST_PASS`)
+    pub accel_overall_passed: bool,
+    /// Overall gyroscope verdict (`GYRO_ST_PASS`), which is `false` if the
+    /// device reported the gyroscope self-test as incomplete
+    pub gyro_overall_passed: bool,
+}
+
+impl SelfTestResult {
+    /// Did both sensors, and every axis of them, pass?
+    pub fn passed(&self) -> bool {
+        self.accel_overall_passed
+            && self.gyro_overall_passed
+            && self
+                .accel_passed
+                .iter()
+                .chain(&self.gyro_passed)
+                .all(|p| *p)
+    }
+}
+
+impl<I2C, E> Icm42670<I2C>
+where
+    I2C: I2c<Error = E>,
+    E: Debug,
+{
+    /// Run the accelerometer and gyroscope self-test
+    ///
+    /// The device must be kept stationary for the duration of the test, which
+    /// takes roughly two seconds. The sensors are temporarily reconfigured to
+    /// the ranges the factory trim values were recorded at, the factory trim
+    /// values are loaded from OTP, and the on-chip self-test of each sensor is
+    /// run in turn. The previous configuration is restored afterwards.
+    ///
+    /// Returns [SensorError::SelfTestTimeout] if the device does not report
+    /// completion of the self-test.
+    pub fn self_test(&mut self, delay: &mut impl DelayNs) -> Result<SelfTestResult, Error<E>> {
+        let power_mode = self.power_mode;
+        let pwr_mgmt0 = self.read_reg(&Bank0::PWR_MGMT0)?;
+        let gyro_config0 = self.read_reg(&Bank0::GYRO_CONFIG0)?;
+        let accel_config0 = self.read_reg(&Bank0::ACCEL_CONFIG0)?;
+
+        // Turn the sensors off first, so that the output data rates may be
+        // changed regardless of the current power mode.
+        self.set_power_mode(PowerMode::Sleep)?;
+        delay.delay_us(200);
+
+        self.set_accel_range(SELF_TEST_ACCEL_RANGE)?;
+        self.set_gyro_range(SELF_TEST_GYRO_RANGE)?;
+        self.set_accel_odr(AccelOdr::Hz800)?;
+        self.set_gyro_odr(GyroOdr::Hz800)?;
+        self.set_power_mode(PowerMode::SixAxisLowNoise)?;
+
+        // The gyroscope requires up to 45ms to start up.
+        delay.delay_ms(50);
+
+        // The MREG registers can only be accessed once the sensors are running.
+        // Always attempt to disable the self-test and restore the previous
+        // configuration, even if the test itself failed part-way through.
+        let (result, restored) = match self.read_self_test_mregs(delay) {
+            Ok(saved) => {
+                let result = self.run_self_test(delay);
+                (result, self.restore_self_test_mregs(delay, saved))
+            }
+            Err(e) => (Err(e), Ok(())),
+        };
+
+        let restored = restored
+            .and_then(|_| self.write_reg(&Bank0::GYRO_CONFIG0, gyro_config0))
+            .and_then(|_| self.write_reg(&Bank0::ACCEL_CONFIG0, accel_config0))
+            .and_then(|_| self.write_reg(&Bank0::PWR_MGMT0, pwr_mgmt0));
+        self.power_mode = power_mode;
+
+        let result = result?;
+        restored?;
+
+        Ok(result)
+    }
+
+    /// Read the MREG registers modified by the self-test: `ST_CONFIG`,
+    /// `OTP_CONFIG` and `OTP_CTRL7`, in that order.
+    fn read_self_test_mregs(&mut self, delay: &mut impl DelayNs) -> Result<[u8; 3], Error<E>> {
+        Ok([
+            self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::ST_CONFIG)?,
+            self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::OTP_CONFIG)?,
+            self.read_mreg(delay, RegisterBank::MReg2, &Mreg2::OTP_CTRL7)?,
+        ])
+    }
+
+    /// Disable the self-test and restore the MREG registers saved by
+    /// [Self::read_self_test_mregs].
+    fn restore_self_test_mregs(
+        &mut self,
+        delay: &mut impl DelayNs,
+        saved: [u8; 3],
+    ) -> Result<(), Error<E>> {
+        let [st_config, otp_config, otp_ctrl7] = saved;
+
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::SELFTEST, 0x00)?;
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::ST_CONFIG, st_config)?;
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::OTP_CONFIG, otp_config)?;
+        self.write_mreg(delay, RegisterBank::MReg2, &Mreg2::OTP_CTRL7, otp_ctrl7)
+    }
+
+    fn run_self_test(&mut self, delay: &mut impl DelayNs) -> Result<SelfTestResult, Error<E>> {
+        // The on-chip self-test compares against the factory trim values, so
+        // these must be loaded from OTP first.
+        let factory = self.load_self_test_trim(delay)?;
+
+        let (accel_response, gyro_response) = self.measure_response(delay)?;
+
+        self.write_mreg(
+            delay,
+            RegisterBank::MReg1,
+            &Mreg1::ST_CONFIG,
+            ST_CONFIG_VALUE,
+        )?;
+
+        self.run_on_chip_self_test(delay, SELFTEST_ACCEL)?;
+        // `ACCEL_ST_PASS` occupies bit 6 and `AZ_ST_PASS`, `AY_ST_PASS` and
+        // `AX_ST_PASS` bits 5:3
+        let status1 = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::ST_STATUS1)?;
+
+        self.run_on_chip_self_test(delay, SELFTEST_GYRO)?;
+        // `GYRO_ST_PASS` occupies bit 6, `GZ_ST_PASS`, `GY_ST_PASS` and
+        // `GX_ST_PASS` bits 5:3, and `ST_INCOMPLETE` bit 2
+        let status2 = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::ST_STATUS2)?;
+
+        let accel_scale = SELF_TEST_ACCEL_RANGE.scale_factor();
+        let gyro_scale = SELF_TEST_GYRO_RANGE.scale_factor();
+
+        let ratio = |response: f32, code: u8| match code {
+            0 => None,
+            code => Some(abs(response) / factory_response(code)),
+        };
+
+        Ok(SelfTestResult {
+            accel_response: F32x3::new(
+                accel_response[0] / accel_scale,
+                accel_response[1] / accel_scale,
+                accel_response[2] / accel_scale,
+            ),
+            gyro_response: F32x3::new(
+                gyro_response[0] / gyro_scale,
+                gyro_response[1] / gyro_scale,
+                gyro_response[2] / gyro_scale,
+            ),
+            accel_ratio: [
+                ratio(accel_response[0], factory[0]),
+                ratio(accel_response[1], factory[1]),
+                ratio(accel_response[2], factory[2]),
+            ],
+            gyro_ratio: [
+                ratio(gyro_response[0], factory[3]),
+                ratio(gyro_response[1], factory[4]),
+                ratio(gyro_response[2], factory[5]),
+            ],
+            accel_passed: axis_verdicts(status1),
+            gyro_passed: axis_verdicts(status2),
+            accel_overall_passed: status1 & 0b0100_0000 != 0,
+            gyro_overall_passed: status2 & 0b0100_0000 != 0 && status2 & 0b0000_0100 == 0,
+        })
+    }
+
+    /// Trigger the on-chip self-test selected by `selftest` and block until
+    /// the device reports its completion through `ST_INT`.
+    fn run_on_chip_self_test(
+        &mut self,
+        delay: &mut impl DelayNs,
+        selftest: u8,
+    ) -> Result<(), Error<E>> {
+        // Clear any stale interrupt status before starting the test.
+        self.read_reg(&Bank0::INT_STATUS)?;
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::SELFTEST, selftest)?;
+
+        let mut done = false;
+        for _ in 0..ST_POLL_ATTEMPTS {
+            delay.delay_ms(10);
+
+            // `ST_INT` occupies bit 7 in the register
+            if self.read_reg(&Bank0::INT_STATUS)? & 0b1000_0000 != 0 {
+                done = true;
+                break;
+            }
+        }
+
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::SELFTEST, 0x00)?;
+
+        if done {
+            Ok(())
+        } else {
+            Err(Error::SensorError(SensorError::SelfTestTimeout))
+        }
+    }
+
+    /// Measure the raw response of each accelerometer and gyroscope axis to the
+    /// self-test excitation, in the order accel X/Y/Z followed by gyro X/Y/Z.
+    fn measure_response(
+        &mut self,
+        delay: &mut impl DelayNs,
+    ) -> Result<([f32; 3], [f32; 3]), Error<E>> {
+        let period = sample_period_us(AccelOdr::Hz800.as_f32());
+
+        let (accel_off, gyro_off) = self.average_raw(delay, period)?;
+
+        self.write_mreg(
+            delay,
+            RegisterBank::MReg1,
+            &Mreg1::SELFTEST,
+            SELFTEST_EXCITATION,
+        )?;
+        delay.delay_ms(100);
+
+        let (accel_on, gyro_on) = self.average_raw(delay, period)?;
+
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::SELFTEST, 0x00)?;
+        delay.delay_ms(100);
+
+        let mut accel = [0.0; 3];
+        let mut gyro = [0.0; 3];
+        for axis in 0..3 {
+            accel[axis] = accel_on[axis] - accel_off[axis];
+            gyro[axis] = gyro_on[axis] - gyro_off[axis];
+        }
+
+        Ok((accel, gyro))
+    }
+
+    /// Average the raw accelerometer and gyroscope output over the self-test
+    /// sample count.
+    fn average_raw(
+        &mut self,
+        delay: &mut impl DelayNs,
+        period: u32,
+    ) -> Result<([f32; 3], [f32; 3]), Error<E>> {
+        let mut accel = [0.0; 3];
+        let mut gyro = [0.0; 3];
+
+        for _ in 0..SELF_TEST_SAMPLES {
+            delay.delay_us(period);
+
+            accumulate(&mut accel, self.read_accel_raw()?);
+            accumulate(&mut gyro, self.gyro_raw()?);
+        }
+
+        for value in accel.iter_mut().chain(gyro.iter_mut()) {
+            *value /= SELF_TEST_SAMPLES as f32;
+        }
+
+        Ok((accel, gyro))
+    }
+
+    /// Copy the factory self-test trim values from OTP into MREG3 and read
+    /// them, in the order accel X/Y/Z followed by gyro X/Y/Z.
+    ///
+    /// This modifies `OTP_CONFIG` and `OTP_CTRL7`, which must be restored by
+    /// the caller.
+    fn load_self_test_trim(&mut self, delay: &mut impl DelayNs) -> Result<[u8; 6], Error<E>> {
+        // `OTP_COPY_MODE` occupies bits 3:2 in `OTP_CONFIG`; 0b11 selects the
+        // self-test data.
+        let otp_config = self.read_mreg(delay, RegisterBank::MReg1, &Mreg1::OTP_CONFIG)?;
+        self.write_mreg(
+            delay,
+            RegisterBank::MReg1,
+            &Mreg1::OTP_CONFIG,
+            (otp_config & !0b0000_1100) | 0b0000_1100,
+        )?;
+
+        // Power up the OTP (`OTP_PWR_DOWN`, bit 1) and then trigger a reload
+        // (`OTP_RELOAD`, bit 3).
+        let otp_ctrl7 = self.read_mreg(delay, RegisterBank::MReg2, &Mreg2::OTP_CTRL7)?;
+        let otp_ctrl7 = otp_ctrl7 & !0b0000_0010;
+        self.write_mreg(delay, RegisterBank::MReg2, &Mreg2::OTP_CTRL7, otp_ctrl7)?;
+        delay.delay_us(300);
+
+        self.write_mreg(
+            delay,
+            RegisterBank::MReg2,
+            &Mreg2::OTP_CTRL7,
+            otp_ctrl7 | 0b0000_1000,
+        )?;
+        delay.delay_us(280);
+
+        let mut trim = [0u8; 6];
+        let regs = [
+            Mreg3::XA_ST_DATA,
+            Mreg3::YA_ST_DATA,
+            Mreg3::ZA_ST_DATA,
+            Mreg3::XG_ST_DATA,
+            Mreg3::YG_ST_DATA,
+            Mreg3::ZG_ST_DATA,
+        ];
+        for (value, reg) in trim.iter_mut().zip(regs) {
+            *value = self.read_mreg(delay, RegisterBank::MReg3, &reg)?;
+        }
+
+        Ok(trim)
+    }
+}
+
+/// Decode the per-axis `X`, `Y` and `Z` pass bits (bits 3, 4 and 5) of
+/// `ST_STATUS1` or `ST_STATUS2`
+fn axis_verdicts(status: u8) -> [bool; 3] {
+    [
+        status & 0b0000_1000 != 0,
+        status & 0b0001_0000 != 0,
+        status & 0b0010_0000 != 0,
+    ]
+}
+
+fn accumulate(sum: &mut [f32; 3], raw: I16x3) {
+    sum[0] += raw.x as f32;
+    sum[1] += raw.y as f32;
+    sum[2] += raw.z as f32;
+}
+
+/// Approximate expected self-test response, in LSB, encoded by a factory trim
+/// code
+fn factory_response(code: u8) -> f32 {
+    // The factory response is given by `2620 * 1.01^(code - 1)`.
+    let mut response = 2620.0;
+    for _ in 1..code {
+        response *= 1.01;
+    }
+
+    response
+}
@@ -0,0 +1,293 @@
+use core::fmt::Debug;
+
+use embedded_hal::{delay::DelayNs, i2c::I2c};
+
+use crate::{
+    config::{Bitfield, WomEnable},
+    error::SensorError,
+    register::{Bank0, Mreg1, RegisterBank},
+    AccLpAvg,
+    AccelOdr,
+    Error,
+    Icm42670,
+    InterruptPin,
+    PowerMode,
+    WomIntDur,
+    WomIntMode,
+    WomMode,
+};
+
+/// Wake-on-Motion threshold registers of the X, Y and Z axes
+const WOM_THRESHOLDS: [Mreg1; 3] = [
+    Mreg1::ACCEL_WOM_X_THR,
+    Mreg1::ACCEL_WOM_Y_THR,
+    Mreg1::ACCEL_WOM_Z_THR,
+];
+
+/// Wake-on-Motion configuration
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WomConfig {
+    /// Threshold for the X axis, in mg (0 to 1000)
+    pub x_threshold_mg: u16,
+    /// Threshold for the Y axis, in mg (0 to 1000)
+    pub y_threshold_mg: u16,
+    /// Threshold for the Z axis, in mg (0 to 1000)
+    pub z_threshold_mg: u16,
+    /// Whether any or all axes must exceed their thresholds
+    pub int_mode: WomIntMode,
+    /// Sample against which new samples are compared
+    pub mode: WomMode,
+    /// Number of over-threshold events before the interrupt is asserted
+    pub int_dur: WomIntDur,
+}
+
+impl Default for WomConfig {
+    fn default() -> Self {
+        Self {
+            x_threshold_mg: 100,
+            y_threshold_mg: 100,
+            z_threshold_mg: 100,
+            int_mode: WomIntMode::default(),
+            mode: WomMode::default(),
+            int_dur: WomIntDur::default(),
+        }
+    }
+}
+
+/// Axes on which Wake-on-Motion was detected
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct WomStatus {
+    /// Motion detected on the X axis
+    pub x: bool,
+    /// Motion detected on the Y axis
+    pub y: bool,
+    /// Motion detected on the Z axis
+    pub z: bool,
+}
+
+impl WomStatus {
+    /// Was motion detected on any axis?
+    pub fn any(&self) -> bool {
+        self.x || self.y || self.z
+    }
+
+    pub(crate) fn from_bits(bits: u8) -> Self {
+        // `WOM_X_INT`, `WOM_Y_INT` and `WOM_Z_INT` occupy bits 2, 1 and 0
+        Self {
+            x: bits & 0b100 != 0,
+            y: bits & 0b010 != 0,
+            z: bits & 0b001 != 0,
+        }
+    }
+}
+
+/// Configuration which was active before entering the low-power
+/// Wake-on-Motion profile, used to restore it on wake
+///
+/// Returned by [Icm42670::enter_wom_profile].
+#[must_use = "the previous configuration is lost if this is dropped"]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WomProfileGuard {
+    pin: InterruptPin,
+    power_mode: PowerMode,
+    pwr_mgmt0: u8,
+    accel_config0: u8,
+    accel_config1: u8,
+    wom_config: u8,
+    int_source: u8,
+    thresholds: [u8; 3],
+}
+
+impl WomProfileGuard {
+    /// Restore the configuration which was active before entering the
+    /// low-power Wake-on-Motion profile
+    pub fn restore<I2C, E>(
+        self,
+        imu: &mut Icm42670<I2C>,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), Error<E>>
+    where
+        I2C: I2c<Error = E>,
+        E: Debug,
+    {
+        // Disable Wake-on-Motion before touching the accelerometer configuration.
+        imu.write_reg(&Bank0::WOM_CONFIG, self.wom_config & !WomEnable::BITMASK)?;
+        imu.write_reg(&self.pin.source1(), self.int_source)?;
+
+        // The accelerometer configuration may only be changed while the sensors
+        // are off.
+        imu.set_power_mode(PowerMode::Sleep)?;
+        delay.delay_us(200);
+
+        imu.write_reg(&Bank0::ACCEL_CONFIG0, self.accel_config0)?;
+        imu.write_reg(&Bank0::ACCEL_CONFIG1, self.accel_config1)?;
+        imu.write_reg(&Bank0::PWR_MGMT0, self.pwr_mgmt0)?;
+        imu.power_mode = self.power_mode;
+
+        // Sensors require some time to start up after changing the power mode.
+        delay.delay_ms(1);
+
+        // The thresholds live in MREG1, so can only be restored once the internal
+        // clock is running again.
+        for (reg, value) in WOM_THRESHOLDS.iter().zip(self.thresholds) {
+            imu.write_mreg(delay, RegisterBank::MReg1, reg, value)?;
+        }
+
+        imu.write_reg(&Bank0::WOM_CONFIG, self.wom_config)
+    }
+}
+
+impl<I2C, E> Icm42670<I2C>
+where
+    I2C: I2c<Error = E>,
+    E: Debug,
+{
+    /// Put the device into a low-power state in which only the accelerometer is
+    /// running, with Wake-on-Motion enabled and routed to the given pin
+    ///
+    /// The device must not be in [PowerMode::Sleep] when this is called, as the
+    /// Wake-on-Motion thresholds can only be written while the internal clock
+    /// is running. The returned guard can be used to restore the previous
+    /// configuration once motion has been detected.
+    pub fn enter_wom_profile(
+        &mut self,
+        delay: &mut impl DelayNs,
+        odr: AccelOdr,
+        avg: AccLpAvg,
+        config: &WomConfig,
+        pin: InterruptPin,
+    ) -> Result<WomProfileGuard, Error<E>> {
+        // Validate everything up front, so that the device is left untouched if
+        // the profile cannot be entered.
+        if !odr.is_supported_in(PowerMode::AccelLowPower) {
+            return Err(Error::SensorError(SensorError::UnsupportedOdrForPowerMode));
+        }
+        wom_threshold(config.x_threshold_mg)?;
+        wom_threshold(config.y_threshold_mg)?;
+        wom_threshold(config.z_threshold_mg)?;
+
+        let mut thresholds = [0u8; 3];
+        for (value, reg) in thresholds.iter_mut().zip(&WOM_THRESHOLDS) {
+            *value = self.read_mreg(delay, RegisterBank::MReg1, reg)?;
+        }
+
+        let guard = WomProfileGuard {
+            pin,
+            power_mode: self.power_mode,
+            pwr_mgmt0: self.read_reg(&Bank0::PWR_MGMT0)?,
+            accel_config0: self.read_reg(&Bank0::ACCEL_CONFIG0)?,
+            accel_config1: self.read_reg(&Bank0::ACCEL_CONFIG1)?,
+            wom_config: self.read_reg(&Bank0::WOM_CONFIG)?,
+            int_source: self.read_reg(&pin.source1())?,
+            thresholds,
+        };
+
+        // Thresholds live in MREG1, so must be written before the internal clock
+        // is allowed to stop in low-power mode.
+        self.disable_wom()?;
+        self.configure_wom(delay, config)?;
+
+        // See "WAKE ON MOTION PROGRAMMING" in the datasheet for the sequence below.
+        // The sensors are turned off first, as neither the averaging nor a
+        // low-power-only output data rate may be set in low-noise mode.
+        self.set_power_mode(PowerMode::Sleep)?;
+        delay.delay_us(200);
+
+        self.set_accel_odr(odr)?;
+        self.set_accel_low_power_avg(avg)?;
+        self.set_power_mode(PowerMode::AccelLowPower)?;
+        delay.delay_ms(1);
+
+        self.set_wom_interrupt(pin, true)?;
+        delay.delay_ms(50);
+
+        self.enable_wom()?;
+
+        Ok(guard)
+    }
+
+    /// Return the configured Wake-on-Motion thresholds and behaviour
+    ///
+    /// Thresholds are rounded to the nearest mg.
+    pub fn wom_config(&mut self, delay: &mut impl DelayNs) -> Result<WomConfig, Error<E>> {
+        let mut thresholds = [0u16; 3];
+        for (value, reg) in thresholds.iter_mut().zip(&WOM_THRESHOLDS) {
+            let lsb = self.read_mreg(delay, RegisterBank::MReg1, reg)?;
+            *value = ((lsb as u32 * 1000 + 128) / 256) as u16;
+        }
+
+        Ok(WomConfig {
+            x_threshold_mg: thresholds[0],
+            y_threshold_mg: thresholds[1],
+            z_threshold_mg: thresholds[2],
+            int_mode: self.read_field()?,
+            mode: self.read_field()?,
+            int_dur: self.read_field()?,
+        })
+    }
+
+    /// Configure the Wake-on-Motion thresholds and behaviour
+    ///
+    /// This does not enable Wake-on-Motion; to do so call [Self::enable_wom]
+    /// once the accelerometer is running.
+    pub fn configure_wom(
+        &mut self,
+        delay: &mut impl DelayNs,
+        config: &WomConfig,
+    ) -> Result<(), Error<E>> {
+        let x = wom_threshold(config.x_threshold_mg)?;
+        let y = wom_threshold(config.y_threshold_mg)?;
+        let z = wom_threshold(config.z_threshold_mg)?;
+
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::ACCEL_WOM_X_THR, x)?;
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::ACCEL_WOM_Y_THR, y)?;
+        self.write_mreg(delay, RegisterBank::MReg1, &Mreg1::ACCEL_WOM_Z_THR, z)?;
+
+        self.update_reg(config.int_mode)?;
+        self.update_reg(config.mode)?;
+        self.update_reg(config.int_dur)?;
+
+        Ok(())
+    }
+
+    /// Enable Wake-on-Motion
+    pub fn enable_wom(&mut self) -> Result<(), Error<E>> {
+        self.update_reg(WomEnable::Enabled)
+    }
+
+    /// Disable Wake-on-Motion
+    pub fn disable_wom(&mut self) -> Result<(), Error<E>> {
+        self.update_reg(WomEnable::Disabled)
+    }
+
+    /// Enable or disable routing of the Wake-on-Motion interrupt for all three
+    /// axes to the given pin
+    pub fn set_wom_interrupt(&mut self, pin: InterruptPin, enabled: bool) -> Result<(), Error<E>> {
+        // `WOM_X_INTn_EN`, `WOM_Y_INTn_EN` and `WOM_Z_INTn_EN` occupy bits 2:0
+        let bits = if enabled { 0b111 } else { 0b000 };
+
+        self.modify_reg(&pin.source1(), 0b0000_0111, bits)
+    }
+
+    /// Read and clear the Wake-on-Motion interrupt status
+    ///
+    /// Note that reading this status also clears the significant motion
+    /// detection interrupt status, as they share a register.
+    pub fn wom_status(&mut self) -> Result<WomStatus, Error<E>> {
+        let bits = self.read_reg(&Bank0::INT_STATUS2)?;
+
+        Ok(WomStatus::from_bits(bits))
+    }
+}
+
+/// Convert a Wake-on-Motion threshold in mg to its register value, which has a
+/// resolution of 1/256 g
+pub(crate) fn wom_threshold(mg: u16) -> Result<u8, SensorError> {
+    if mg > 1000 {
+        return Err(SensorError::ThresholdOutOfRange);
+    }
+
+    let lsb = (mg as u32 * 256 + 500) / 1000;
+
+    Ok(lsb.min(255) as u8)
+}